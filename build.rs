@@ -4,6 +4,20 @@ use std::env;
 
 fn main() {
     println!("cargo:rustc-check-cfg=cfg(c_implementation_active)");
+
+    // Build metadata recorded in result exports
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    if let Ok(output) = std::process::Command::new(rustc).arg("--version").output() {
+        println!(
+            "cargo:rustc-env=RUSTC_VERSION={}",
+            String::from_utf8_lossy(&output.stdout).trim()
+        );
+    }
+    println!(
+        "cargo:rustc-env=TARGET_FEATURES={}",
+        env::var("CARGO_CFG_TARGET_FEATURE").unwrap_or_default()
+    );
+
     // Check for C compiler compatibility and type
     let build = cc::Build::new();
     let compiler = build.get_compiler();
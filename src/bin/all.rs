@@ -1,46 +1,119 @@
 //! Generic CLI for running algorithms.
 //!
 //! Usage:
-//!   micro-algo              # Run all algorithms
-//!   micro-algo --list       # List available algorithms
+//!   micro-algo              # Run all algorithms (same as `micro-algo run`)
 //!   micro-algo dot_product  # Run specific algorithm
+//!   micro-algo dot_product xoroshiro128++  # Run several algorithms
+//!   micro-algo --category math,random      # Run whole categories
+//!   micro-algo verify       # Check all variants against their reference (exit code 1 on failure)
+//!   micro-algo list         # List algorithms and variants
+//!   micro-algo info         # Show machine, build and timer diagnostics
+//!   micro-algo compare base.json new.json  # Compare two result files
 //!   micro-algo --help       # Show help
 
-use micro_optimize_algo::registry::build_registry;
-use micro_optimize_algo::PinStrategy;
+use micro_optimize_algo::registry::{build_registry, closest_match, AlgorithmRegistry, AlgorithmRunner, SizeSpace};
+use micro_optimize_algo::utils::bench::{calibrate_overhead, CALIBRATION_SAMPLES};
+use micro_optimize_algo::utils::cache::CacheMode;
+use micro_optimize_algo::utils::clock::{self, ClockKind};
+use micro_optimize_algo::utils::config::{AlgorithmSelection, ConfigFile};
+use micro_optimize_algo::utils::filter::parse_patterns;
+use micro_optimize_algo::utils::cpu_affinity::{
+    benchmark_cores, check_cores, get_current_cpu, parse_cpu_list,
+};
+use micro_optimize_algo::utils::params::parse_selection;
+use micro_optimize_algo::utils::preflight::Preflight;
+use micro_optimize_algo::utils::report::{compare, Verdict, DEFAULT_ALPHA};
+use micro_optimize_algo::utils::{BuildInfo, MachineInfo, RunReport};
+use micro_optimize_algo::{tui, Batching, BenchmarkOptions, Language, Parameter, PinStrategy};
 use std::env;
 
+/// Subcommand of the CLI (`run` when none is given)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Command {
+    Run,
+    Verify,
+    List,
+    Info,
+    Compare,
+}
+
+impl Command {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "run" => Some(Command::Run),
+            "verify" => Some(Command::Verify),
+            "list" => Some(Command::List),
+            "info" => Some(Command::Info),
+            "compare" => Some(Command::Compare),
+            _ => None,
+        }
+    }
+}
+
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
     let registry = build_registry();
 
-    // Parse arguments
-    let mut show_list = false;
+    // The subcommand is optional; anything else is parsed as options of `run`
+    let mut command = Command::Run;
+    if let Some(parsed) = args.get(1).and_then(|a| Command::parse(a)) {
+        command = parsed;
+        args.remove(1);
+    }
+
+    // Parse arguments (a config file first, so that command-line options override it)
     let mut show_help = false;
-    let mut sample_sizes: Vec<usize> = vec![64, 256, 1024, 4096, 16384];
-    let mut runs: usize = 30;
-    let mut seed: Option<u64> = None;
-    let mut csv_path: Option<String> = None;
-    let mut algorithm_filter: Option<String> = None;
-    let mut filter_outliers: bool = false;
-    let mut pin_strategy: PinStrategy = PinStrategy::PerExecution;
+    let mut options = BenchmarkOptions::default();
+    let mut selection = AlgorithmSelection::default();
+    if let Err(e) = load_config(&args, &mut options, &mut selection) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    let mut algorithm_names: Vec<String> = Vec::new();
+    let mut categories: Vec<String> = Vec::new();
 
     let mut i = 1;
     while i < args.len() {
         match args[i].as_str() {
-            "--list" | "-l" => show_list = true,
+            "--list" | "-l" => command = Command::List,
             "--help" | "-h" => show_help = true,
             "--sizes" => {
                 i += 1;
                 if i < args.len() {
-                    sample_sizes = args[i]
+                    // `ALGO=SIZES` overrides the sizes of one algorithm only
+                    let (algorithm, list) = match args[i].split_once('=') {
+                        Some((algorithm, list)) => (Some(algorithm.trim()), list),
+                        None => (None, args[i].as_str()),
+                    };
+                    let sizes: Vec<usize> = list
                         .split(',')
                         .filter_map(|s| s.trim().parse().ok())
+                        .filter(|&n| n > 0)
                         .collect();
-                    if sample_sizes.is_empty() {
+                    if sizes.is_empty() {
                         eprintln!("Error: --sizes requires valid comma-separated integers");
                         std::process::exit(1);
                     }
+                    match algorithm {
+                        Some(algorithm) => {
+                            match registry.find(algorithm) {
+                                None => {
+                                    eprintln!("Error: --sizes: unknown algorithm '{}'", algorithm);
+                                    std::process::exit(1);
+                                }
+                                Some(algo) if algo.size_space() == SizeSpace::Independent => {
+                                    eprintln!("Note: {} is size-independent; --sizes is ignored for it", algorithm);
+                                }
+                                Some(_) => {}
+                            }
+                            options.algorithm_sizes.retain(|(name, _)| name != algorithm);
+                            options.algorithm_sizes.push((algorithm.to_string(), sizes));
+                        }
+                        None => {
+                            options.input_sizes = Some(sizes);
+                            options.algorithm_sizes.clear();
+                        }
+                    }
                 } else {
                     eprintln!("Error: --sizes requires a value (e.g., --sizes 64,256,1024)");
                     std::process::exit(1);
@@ -49,7 +122,7 @@ fn main() {
             "--iter" | "--runs" | "-r" => {
                 i += 1;
                 if i < args.len() {
-                    runs = match args[i].parse() {
+                    options.timing.runs_per_variant = match args[i].parse() {
                         Ok(n) if n > 0 => n,
                         _ => {
                             eprintln!("Error: --iter requires a positive integer");
@@ -61,10 +134,55 @@ fn main() {
                     std::process::exit(1);
                 }
             }
+            "--precision" => {
+                i += 1;
+                if i < args.len() {
+                    options.timing.target_precision = match args[i].trim_end_matches('%').parse::<f64>() {
+                        Ok(pct) if pct > 0.0 => Some(pct / 100.0),
+                        _ => {
+                            eprintln!("Error: --precision requires a positive percentage");
+                            std::process::exit(1);
+                        }
+                    };
+                } else {
+                    eprintln!("Error: --precision requires a value (e.g., --precision 1 for ±1%)");
+                    std::process::exit(1);
+                }
+            }
+            "--time-budget" => {
+                i += 1;
+                if i < args.len() {
+                    options.timing.time_budget = match args[i].parse::<f64>() {
+                        Ok(secs) if secs > 0.0 => Some(std::time::Duration::from_secs_f64(secs)),
+                        _ => {
+                            eprintln!("Error: --time-budget requires a positive number of seconds");
+                            std::process::exit(1);
+                        }
+                    };
+                } else {
+                    eprintln!("Error: --time-budget requires a value (e.g., --time-budget 0.5)");
+                    std::process::exit(1);
+                }
+            }
+            "--max-runs" => {
+                i += 1;
+                if i < args.len() {
+                    options.timing.max_runs_per_variant = match args[i].parse() {
+                        Ok(n) if n > 0 => n,
+                        _ => {
+                            eprintln!("Error: --max-runs requires a positive integer");
+                            std::process::exit(1);
+                        }
+                    };
+                } else {
+                    eprintln!("Error: --max-runs requires a value (e.g., --max-runs 50000)");
+                    std::process::exit(1);
+                }
+            }
             "--seed" => {
                 i += 1;
                 if i < args.len() {
-                    seed = match args[i].parse() {
+                    options.seed = match args[i].parse() {
                         Ok(n) => Some(n),
                         Err(_) => {
                             eprintln!("Error: --seed requires a valid integer");
@@ -79,23 +197,213 @@ fn main() {
             "--csv" => {
                 i += 1;
                 if i < args.len() {
-                    csv_path = Some(args[i].clone());
+                    options.csv_path = Some(args[i].clone());
                 } else {
                     eprintln!("Error: --csv requires a file path (e.g., --csv results.csv)");
                     std::process::exit(1);
                 }
             }
+            "--json" => {
+                i += 1;
+                if i < args.len() {
+                    options.json_path = Some(args[i].clone());
+                } else {
+                    eprintln!("Error: --json requires a file path (e.g., --json results.json)");
+                    std::process::exit(1);
+                }
+            }
+            "--raw-csv" => {
+                i += 1;
+                if i < args.len() {
+                    options.raw_csv_path = Some(args[i].clone());
+                } else {
+                    eprintln!("Error: --raw-csv requires a file path (e.g., --raw-csv samples.csv)");
+                    std::process::exit(1);
+                }
+            }
+            "--save-baseline" => {
+                i += 1;
+                if i < args.len() {
+                    options.save_path = Some(args[i].clone());
+                } else {
+                    eprintln!("Error: --save-baseline requires a file path (e.g., --save-baseline baseline.json)");
+                    std::process::exit(1);
+                }
+            }
+            "--compare" => {
+                i += 1;
+                if i < args.len() {
+                    options.compare_path = Some(args[i].clone());
+                } else {
+                    eprintln!("Error: --compare requires a file path (e.g., --compare baseline.json)");
+                    std::process::exit(1);
+                }
+            }
+            "--variants" => {
+                i += 1;
+                if i < args.len() {
+                    options.variant_filter.include.extend(parse_patterns(&args[i]));
+                } else {
+                    eprintln!("Error: --variants requires patterns (e.g., --variants 'dot_product/x86_64-*,*/original')");
+                    std::process::exit(1);
+                }
+            }
+            "--exclude" => {
+                i += 1;
+                if i < args.len() {
+                    options.variant_filter.exclude.extend(parse_patterns(&args[i]));
+                } else {
+                    eprintln!("Error: --exclude requires patterns (e.g., --exclude 'c-*')");
+                    std::process::exit(1);
+                }
+            }
+            "--lang" => {
+                i += 1;
+                if i < args.len() {
+                    options.variant_filter.lang = match Language::parse(&args[i]) {
+                        Some(lang) => Some(lang),
+                        None => {
+                            eprintln!("Error: Unknown language '{}'. Use 'rust' or 'c'.", args[i]);
+                            std::process::exit(1);
+                        }
+                    };
+                } else {
+                    eprintln!("Error: --lang requires a value (e.g., --lang c)");
+                    std::process::exit(1);
+                }
+            }
+            "--arch" => {
+                i += 1;
+                if i < args.len() {
+                    options.variant_filter.arch = Some(args[i].clone());
+                } else {
+                    eprintln!("Error: --arch requires a value (e.g., --arch x86_64)");
+                    std::process::exit(1);
+                }
+            }
+            "--simd" => {
+                i += 1;
+                if i < args.len() {
+                    options.variant_filter.simd = Some(args[i].clone());
+                } else {
+                    eprintln!("Error: --simd requires a value (e.g., --simd avx2)");
+                    std::process::exit(1);
+                }
+            }
+            "--technique" => {
+                i += 1;
+                if i < args.len() {
+                    options.variant_filter.technique = Some(args[i].clone());
+                } else {
+                    eprintln!("Error: --technique requires a value (e.g., --technique asm)");
+                    std::process::exit(1);
+                }
+            }
+            "--param" | "-p" => {
+                i += 1;
+                match args.get(i).and_then(|arg| parse_selection(arg)) {
+                    Some((name, values)) => {
+                        options.param_values.retain(|(n, _)| *n != name);
+                        options.param_values.push((name, values));
+                    }
+                    None => {
+                        eprintln!("Error: --param requires NAME=VALUES (e.g., --param pattern=sorted)");
+                        std::process::exit(1);
+                    }
+                }
+            }
             "--filter" | "-f" => {
-                filter_outliers = true;
+                options.filter_outliers = true;
+            }
+            "--perf" => {
+                options.timing.hw_counters = true;
+            }
+            "--batch" => {
+                i += 1;
+                if i < args.len() {
+                    options.timing.batching = match Batching::parse(&args[i]) {
+                        Some(batching) => batching,
+                        None => {
+                            eprintln!("Error: Unknown batch mode '{}'. Use 'auto', 'off' or a positive integer.", args[i]);
+                            std::process::exit(1);
+                        }
+                    };
+                } else {
+                    eprintln!("Error: --batch requires a value (e.g., --batch auto or --batch 1024)");
+                    std::process::exit(1);
+                }
+            }
+            "--cache" => {
+                i += 1;
+                if i < args.len() {
+                    options.timing.cache = match CacheMode::parse(&args[i]) {
+                        Some(mode) => mode,
+                        None => {
+                            eprintln!(
+                                "Error: Unknown cache mode '{}'. Use 'hot', 'cold-data', 'cold-code' or 'cold'.",
+                                args[i]
+                            );
+                            std::process::exit(1);
+                        }
+                    };
+                } else {
+                    eprintln!("Error: --cache requires a value (e.g., --cache cold-data)");
+                    std::process::exit(1);
+                }
+            }
+            "--clock" => {
+                i += 1;
+                if i < args.len() {
+                    options.timing.clock = match ClockKind::parse(&args[i]) {
+                        Some(clock) => clock,
+                        None => {
+                            eprintln!(
+                                "Error: Unknown clock '{}'. Use 'rdtsc', 'rdtscp', 'monotonic' or 'perf'.",
+                                args[i]
+                            );
+                            std::process::exit(1);
+                        }
+                    };
+                } else {
+                    eprintln!("Error: --clock requires a value (e.g., --clock monotonic)");
+                    std::process::exit(1);
+                }
+            }
+            "--core" => {
+                i += 1;
+                match args.get(i).and_then(|arg| arg.parse::<usize>().ok()) {
+                    Some(core) => options.timing.cores = vec![core],
+                    None => {
+                        eprintln!("Error: --core requires a core number (e.g., --core 2)");
+                        std::process::exit(1);
+                    }
+                }
+            }
+            "--cores" => {
+                i += 1;
+                let cores = args.get(i).map(|arg| parse_cpu_list(arg)).unwrap_or_default();
+                if cores.is_empty() {
+                    eprintln!("Error: --cores requires a list of cores (e.g., --cores 2,3 or --cores 4-7)");
+                    std::process::exit(1);
+                }
+                options.timing.cores = cores;
+            }
+            "--core-classes" => {
+                options.core_classes = true;
+            }
+            "--subtract-overhead" => {
+                options.timing.subtract_overhead = true;
+            }
+            "--strict" => {
+                options.strict = true;
             }
             "--pin" => {
                 i += 1;
                 if i < args.len() {
-                    pin_strategy = match args[i].as_str() {
-                        "global" => PinStrategy::Global,
-                        "per-call" | "per-execution" => PinStrategy::PerExecution,
-                        other => {
-                            eprintln!("Error: Unknown pin strategy '{}'. Use 'global' or 'per-call'.", other);
+                    options.timing.pin_strategy = match PinStrategy::parse(&args[i]) {
+                        Some(strategy) => strategy,
+                        None => {
+                            eprintln!("Error: Unknown pin strategy '{}'. Use 'global' or 'per-call'.", args[i]);
                             std::process::exit(1);
                         }
                     };
@@ -104,8 +412,21 @@ fn main() {
                     std::process::exit(1);
                 }
             }
+            "--config" | "--profile" => {
+                // Already applied by `load_config`
+                i += 1;
+            }
+            "--category" | "-c" => {
+                i += 1;
+                if i < args.len() {
+                    categories.extend(args[i].split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()));
+                } else {
+                    eprintln!("Error: --category requires a value (e.g., --category math,random)");
+                    std::process::exit(1);
+                }
+            }
             arg if !arg.starts_with('-') => {
-                algorithm_filter = Some(arg.to_string());
+                algorithm_names.push(arg.to_string());
             }
             _ => {
                 eprintln!("Error: Unknown option '{}'", args[i]);
@@ -117,54 +438,229 @@ fn main() {
     }
 
     if show_help {
-        micro_optimize_algo::tui::print_help();
+        tui::print_help();
         return;
     }
 
-    if show_list {
-        micro_optimize_algo::tui::print_available_algorithms(&registry);
+    if command == Command::Info {
+        // Selected before calibrating the timer overhead and checking the TSC
+        let cores = benchmark_cores(&options.timing.cores);
+        if let Err(e) = clock::select(options.timing.clock).and_then(|()| check_cores(&cores)) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        // Same cores as a run: the selected ones (an isolated core by default), or the current one
+        let preflight_cores: Vec<usize> = if cores.is_empty() { get_current_cpu().into_iter().collect() } else { cores };
+        let overhead = calibrate_overhead(CALIBRATION_SAMPLES, preflight_cores.first().copied());
+        tui::print_system_info(&MachineInfo::detect(), &BuildInfo::current(), &overhead);
+        println!();
+        tui::print_preflight(&Preflight::run(&preflight_cores));
         return;
     }
 
-    micro_optimize_algo::tui::print_header();
+    if command == Command::Compare {
+        // Positional arguments are the two result files here
+        std::process::exit(compare_files(&algorithm_names));
+    }
 
-    match algorithm_filter {
-        Some(name) => {
-            // Running a single algorithm
-            match registry.find(&name) {
-                Some(algo) => {
-                    let algos = vec![algo];
-                    micro_optimize_algo::run_benchmarks(
-                        &algos,
-                        &sample_sizes,
-                        runs,
-                        seed,
-                        csv_path.as_deref(),
-                        filter_outliers,
-                        pin_strategy,
-                    );
-                }
-                None => {
-                    eprintln!("Algorithm '{}' not found.", name);
-                    eprintln!("Available: {:?}", registry.list_names());
-                    std::process::exit(1);
-                }
+    if !algorithm_names.is_empty() || !categories.is_empty() {
+        selection = AlgorithmSelection { names: algorithm_names, categories };
+    }
+
+    let algos = match select_algorithms(&registry, &selection.names, &selection.categories) {
+        Ok(algos) => algos,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+    };
+
+    if command == Command::Run {
+        if let Err(message) = check_params(&registry, &options.param_values) {
+            eprintln!("Error: {}", message);
+            std::process::exit(1);
+        }
+    }
+
+    match command {
+        Command::List => tui::print_available_algorithms(&algos),
+        Command::Verify => std::process::exit(verify(&algos, &options)),
+        _ => {
+            tui::print_header();
+            if let Err(e) = micro_optimize_algo::run_benchmarks(&algos, &options) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
             }
+
+            println!("Note: Speedup is relative to the first variant (usually 'original').");
+            println!("      CIs are 95% bootstrap intervals; p-values come from a Mann-Whitney U test vs. that variant.");
         }
-        None => {
-            // Running all algorithms
-            let all_algos: Vec<_> = registry.all().iter().map(|a| a.as_ref()).collect();
-            micro_optimize_algo::run_benchmarks(
-                &all_algos,
-                &sample_sizes,
-                runs,
-                seed,
-                csv_path.as_deref(),
-                filter_outliers,
-                pin_strategy,
-            );
+    }
+}
+
+/// Check that every `--param` names a parameter of some algorithm, with declared values
+/// (algorithms without the parameter ignore it, so a profile can cover several)
+fn check_params(registry: &AlgorithmRegistry, selected: &[(String, Vec<String>)]) -> Result<(), String> {
+    let parameters: Vec<Parameter> = registry.all().iter().flat_map(|a| a.parameters()).collect();
+    for (name, values) in selected {
+        let declared: Vec<&Parameter> = parameters.iter().filter(|p| p.name == name).collect();
+        if declared.is_empty() {
+            let names: Vec<&str> = parameters.iter().map(|p| p.name).collect();
+            let hint = closest_match(name, &names)
+                .map(|s| format!(" Did you mean '{}'?", s))
+                .unwrap_or_default();
+            return Err(format!(
+                "No algorithm has a parameter '{}'.{} Parameters: {}",
+                name,
+                hint,
+                if names.is_empty() { "none".to_string() } else { names.join(", ") }
+            ));
+        }
+        for value in values {
+            if !declared.iter().any(|p| p.values.contains(&value.as_str())) {
+                let known: Vec<&str> = declared.iter().flat_map(|p| p.values.iter().copied()).collect();
+                return Err(format!("Unknown value '{}' for parameter '{}'. Values: {}", value, name, known.join(", ")));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Verify the selected algorithms and print a per-variant report.
+///
+/// # Returns
+/// The process exit code: 0 if every variant passed, 1 otherwise.
+fn verify(algos: &[&dyn AlgorithmRunner], options: &BenchmarkOptions) -> i32 {
+    println!("Verifying {} algorithm(s) against their reference variant:", algos.len());
+    let mut checked = 0;
+    let mut failures = 0;
+    for algo in algos {
+        let variants = algo.variants();
+        let checks: Vec<_> = algo
+            .verify_variants()
+            .into_iter()
+            .filter(|c| {
+                let tags = variants.iter().find(|v| v.name == c.name).map(|v| v.tags).unwrap_or_default();
+                options.variant_filter.matches(algo.name(), c.name, &tags)
+            })
+            .collect();
+        checked += checks.len();
+        failures += tui::print_verification_report(*algo, &checks);
+    }
+    println!();
+    if failures == 0 {
+        println!("All {} variant(s) passed.", checked);
+        0
+    } else {
+        println!("{} of {} variant(s) FAILED.", failures, checked);
+        1
+    }
+}
+
+/// Compare two result files (`--json` / `--save-baseline` output).
+///
+/// # Returns
+/// The process exit code: 0 without regressions, 1 if a variant got significantly
+/// slower, 2 on usage or load errors.
+fn compare_files(paths: &[String]) -> i32 {
+    let [baseline_path, current_path] = paths else {
+        eprintln!("Error: compare requires two result files (e.g., compare base.json new.json)");
+        return 2;
+    };
+    let load = |path: &str| {
+        RunReport::load(path).map_err(|e| eprintln!("Error: Failed to load '{}': {}", path, e))
+    };
+    let (Ok(baseline), Ok(current)) = (load(baseline_path), load(current_path)) else {
+        return 2;
+    };
+
+    let comparisons = compare(&baseline, &current, DEFAULT_ALPHA);
+    if comparisons.is_empty() {
+        println!("No results in '{}'.", current_path);
+        return 0;
+    }
+    tui::print_comparison_table(baseline_path, &baseline, &current, &comparisons);
+
+    let regressions = comparisons.iter().filter(|c| c.verdict == Verdict::Slower).count();
+    if regressions > 0 {
+        println!("{} significant regression(s).", regressions);
+        1
+    } else {
+        0
+    }
+}
+
+/// Apply `--config FILE` (and `--profile NAME`) before the other options are parsed
+fn load_config(args: &[String], options: &mut BenchmarkOptions, selection: &mut AlgorithmSelection) -> Result<(), String> {
+    let value_of = |flag: &str| -> Result<Option<&str>, String> {
+        match args.iter().position(|a| a == flag) {
+            Some(idx) => args
+                .get(idx + 1)
+                .map(|v| Some(v.as_str()))
+                .ok_or_else(|| format!("{} requires a value (e.g., --config bench.toml --profile quick)", flag)),
+            None => Ok(None),
+        }
+    };
+
+    let profile = value_of("--profile")?;
+    match value_of("--config")? {
+        Some(path) => ConfigFile::load(path)?.apply(profile, options, selection),
+        None if profile.is_some() => Err("--profile requires --config".to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Resolve positional algorithm names and `--category` values, in the order given
+/// and without duplicates (all algorithms when neither is given)
+fn select_algorithms<'a>(
+    registry: &'a AlgorithmRegistry,
+    names: &[String],
+    categories: &[String],
+) -> Result<Vec<&'a dyn AlgorithmRunner>, String> {
+    if names.is_empty() && categories.is_empty() {
+        return Ok(registry.all().iter().map(|a| a.as_ref()).collect());
+    }
+
+    let mut selected: Vec<&dyn AlgorithmRunner> = Vec::new();
+    let mut add = |algo: &'a dyn AlgorithmRunner| {
+        if !selected.iter().any(|a| a.name() == algo.name()) {
+            selected.push(algo);
+        }
+    };
+
+    for name in names {
+        match registry.find(name) {
+            Some(algo) => add(algo),
+            None => {
+                let hint = registry
+                    .suggest(name)
+                    .map(|s| format!(" Did you mean '{}'?", s))
+                    .unwrap_or_default();
+                return Err(format!(
+                    "Algorithm '{}' not found.{}\nAvailable: {}",
+                    name,
+                    hint,
+                    registry.list_names().join(", ")
+                ));
+            }
+        }
+    }
+
+    for category in categories {
+        let algos = registry.by_category(category);
+        if algos.is_empty() {
+            let hint = closest_match(category, &registry.categories())
+                .map(|s| format!(" Did you mean '{}'?", s))
+                .unwrap_or_default();
+            return Err(format!(
+                "Category '{}' not found.{}\nAvailable: {}",
+                category,
+                hint,
+                registry.categories().join(", ")
+            ));
         }
+        algos.into_iter().for_each(&mut add);
     }
 
-    println!("Note: Speedup is relative to the first variant (usually 'original').");
+    Ok(selected)
 }
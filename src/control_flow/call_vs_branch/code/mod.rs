@@ -7,7 +7,7 @@ pub mod x86_64_asm;
 /// Function signature for the test functions
 pub type TestFn = fn(u32) -> u32;
 
-use crate::utils::VariantInfo;
+use crate::utils::{VariantInfo, VariantTags};
 
 /// Returns all available variants
 pub fn get_variants() -> Vec<VariantInfo<TestFn>> {
@@ -16,6 +16,8 @@ pub fn get_variants() -> Vec<VariantInfo<TestFn>> {
         name: "original",
         description: "Rust function calls (compiler decides inlining)",
         function: original::process_with_calls,
+        required_features: &[],
+        tags: VariantTags::rust(),
     }];
 
     #[cfg(target_arch = "x86_64")]
@@ -24,16 +26,22 @@ pub fn get_variants() -> Vec<VariantInfo<TestFn>> {
             name: "x86_64-asm-call",
             description: "x86_64 assembly with explicit CALL/RET",
             function: x86_64_asm::process_with_calls,
+            required_features: &[],
+            tags: VariantTags::rust().arch("x86_64").technique("asm"),
         });
         variants.push(VariantInfo {
             name: "x86_64-asm-branch",
             description: "x86_64 assembly with JMP branches (no CALL overhead)",
             function: x86_64_asm::process_with_branch,
+            required_features: &[],
+            tags: VariantTags::rust().arch("x86_64").technique("asm"),
         });
         variants.push(VariantInfo {
             name: "x86_64-asm-inline",
             description: "x86_64 assembly fully inlined (no jumps)",
             function: x86_64_asm::process_inline,
+            required_features: &[],
+            tags: VariantTags::rust().arch("x86_64").technique("asm"),
         });
     }
 
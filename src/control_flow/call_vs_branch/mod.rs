@@ -3,19 +3,21 @@
 pub mod code;
 pub mod test;
 
-use crate::registry::{AlgorithmRunner, VariantClosure};
+use crate::control_flow::pattern::{self, PATTERN};
+use crate::registry::{AlgorithmRunner, InputRegion, Parameter, Params, SizeSpace, VariantCheck, VariantClosure, VariantDescriptor};
+use crate::utils::SeededRng;
 use std::sync::Arc;
 
-/// Generate test data
-fn generate_test_data(size: usize, seed: u64) -> Vec<u32> {
-    let mut data = Vec::with_capacity(size);
-    let mut rng = seed;
+/// Exclusive upper bound of the input values (small enough that no step overflows)
+const RANGE: Parameter = Parameter {
+    name: "range",
+    description: "Exclusive upper bound of the input values",
+    values: &["512", "65536"],
+};
 
-    for _ in 0..size {
-        rng = rng.wrapping_mul(6364136223846793005).wrapping_add(1);
-        data.push((rng >> 32) as u32 % 512);
-    }
-    data
+/// Generate test data - values below `range` following `pattern`
+fn generate_test_data(size: usize, range: u32, pattern: &str, seed: u64) -> Vec<u32> {
+    pattern::generate(pattern, size, range, &mut SeededRng::new(seed))
 }
 
 pub struct CallVsBranchRunner;
@@ -33,30 +35,44 @@ impl AlgorithmRunner for CallVsBranchRunner {
         "Comparison between function calls (CALL/RET) and inline code"
     }
 
-    fn available_variants(&self) -> Vec<&'static str> {
-        code::get_variants().iter().map(|v| v.name).collect()
+    fn size_space(&self) -> SizeSpace {
+        // Number of inputs cycled through: short cycles are learnt by the branch
+        // predictor, long ones are not
+        SizeSpace::Defaults(&[64, 16384])
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![PATTERN, RANGE]
+    }
+
+    fn variants(&self) -> Vec<VariantDescriptor> {
+        code::get_variants().iter().map(|v| v.descriptor()).collect()
     }
 
-    fn get_variant_closures<'a>(&'a self, size: usize) -> Vec<VariantClosure<'a>> {
-        let data: Arc<Vec<u32>> = Arc::new(generate_test_data(size, 0x12345678));
+    fn get_variant_closures<'a>(&'a self, size: usize, params: &Params, seed: u64) -> Vec<VariantClosure<'a>> {
+        let range = params.get(RANGE.name).unwrap_or(RANGE.values[0]).parse().unwrap_or(512);
+        let pattern = params.get(PATTERN.name).unwrap_or(PATTERN.values[0]);
+        let data: Arc<Vec<u32>> = Arc::new(generate_test_data(size, range, pattern, seed));
 
         code::get_variants()
             .into_iter()
             .map(|v| {
                 let data = Arc::clone(&data);
                 let func = v.function;
+                let mut idx = 0;
 
                 VariantClosure {
                     name: v.name,
                     description: v.description,
+                    tags: v.tags,
+                    inputs: vec![InputRegion::of(&data)],
                     run: Box::new(move || {
-                        // Timing inside closure - measures entire loop
-                        let (elapsed, _) = crate::measure!({
-                            let mut last_result = 0u32;
-                            for &val in data.iter() {
-                                last_result = std::hint::black_box(func(std::hint::black_box(val)));
-                            }
-                            last_result
+                        // One call per iteration, cycling through the data: the runner
+                        // chooses how many calls are timed together
+                        let (elapsed, _) = crate::measure_batched!({
+                            let val = data[idx];
+                            idx = if idx + 1 == data.len() { 0 } else { idx + 1 };
+                            func(std::hint::black_box(val))
                         });
                         (elapsed, None) // No precision measurement for control flow
                     }),
@@ -65,7 +81,7 @@ impl AlgorithmRunner for CallVsBranchRunner {
             .collect()
     }
 
-    fn verify(&self) -> Result<(), String> {
-        test::verify_all()
+    fn verify_variants(&self) -> Vec<VariantCheck> {
+        test::verify_variants()
     }
 }
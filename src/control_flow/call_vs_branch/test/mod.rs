@@ -1,30 +1,35 @@
 //! Tests for call vs branch implementations
 
 use super::code::{get_variants, original};
+use crate::registry::{first_failure, VariantCheck};
 
-/// Verify all variants produce the same results as the original
-pub fn verify_all() -> Result<(), String> {
+/// Check every variant against the original, one entry per variant
+pub fn verify_variants() -> Vec<VariantCheck> {
     let test_values: Vec<u32> = vec![0, 1, 2, 5, 10, 50, 100, 255, 500, 1000, 10000];
 
-    for variant in get_variants() {
-        if variant.name == "original" {
-            continue;
-        }
+    get_variants()
+        .into_iter()
+        .filter(|v| v.name != "original")
+        .map(|variant| {
+            for &value in &test_values {
+                let expected = original::process_with_calls(value);
+                let actual = (variant.function)(value);
 
-        for &value in &test_values {
-            let expected = original::process_with_calls(value);
-            let actual = (variant.function)(value);
-
-            if actual != expected {
-                return Err(format!(
-                    "Variant '{}' failed for value {}: expected {}, got {}",
-                    variant.name, value, expected, actual
-                ));
+                if actual != expected {
+                    return VariantCheck::failed(
+                        variant.name,
+                        format!("Failed for value {}: expected {}, got {}", value, expected, actual),
+                    );
+                }
             }
-        }
-    }
+            VariantCheck::passed(variant.name)
+        })
+        .collect()
+}
 
-    Ok(())
+/// Verify all variants produce the same results as the original
+pub fn verify_all() -> Result<(), String> {
+    first_failure(&verify_variants())
 }
 
 #[cfg(test)]
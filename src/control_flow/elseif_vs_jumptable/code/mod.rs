@@ -8,7 +8,7 @@ pub mod x86_64_asm;
 /// Function signature: maps an opcode (0-7) to a multiplier
 pub type DispatchFn = fn(u8, u32) -> u32;
 
-use crate::utils::VariantInfo;
+use crate::utils::{VariantInfo, VariantTags};
 
 /// Returns all available variants
 pub fn get_variants() -> Vec<VariantInfo<DispatchFn>> {
@@ -17,6 +17,8 @@ pub fn get_variants() -> Vec<VariantInfo<DispatchFn>> {
         name: "original",
         description: "Rust match expression (compiler-optimized)",
         function: original::dispatch_operation,
+        required_features: &[],
+        tags: VariantTags::rust(),
     }];
 
     #[cfg(target_arch = "x86_64")]
@@ -25,16 +27,22 @@ pub fn get_variants() -> Vec<VariantInfo<DispatchFn>> {
             name: "x86_64-asm-branch",
             description: "x86_64 assembly with conditional branches (Jcc)",
             function: x86_64_asm::dispatch_branch,
+            required_features: &[],
+            tags: VariantTags::rust().arch("x86_64").technique("asm"),
         });
         variants.push(VariantInfo {
             name: "x86_64-asm-jumptable",
             description: "x86_64 assembly with indexed jump table lookup",
             function: x86_64_asm::dispatch_jumptable,
+            required_features: &[],
+            tags: VariantTags::rust().arch("x86_64").technique("asm"),
         });
         variants.push(VariantInfo {
             name: "x86_64-asm-branchless",
             description: "x86_64 assembly branchless with CMOV",
             function: x86_64_asm::dispatch_branchless,
+            required_features: &[],
+            tags: VariantTags::rust().arch("x86_64").technique("asm"),
         });
     }
 
@@ -44,12 +52,16 @@ pub fn get_variants() -> Vec<VariantInfo<DispatchFn>> {
             name: "c-elseif",
             description: "C if-else if chain",
             function: c_impl::dispatch_operation_c_elseif,
+            required_features: &[],
+            tags: VariantTags::c(),
         });
 
         variants.push(VariantInfo {
             name: "c-switch",
             description: "C switch statement (likely jumptable)",
             function: c_impl::dispatch_operation_c_switch,
+            required_features: &[],
+            tags: VariantTags::c(),
         });
     }
 
@@ -3,21 +3,19 @@
 pub mod code;
 pub mod test;
 
-use crate::registry::{AlgorithmRunner, VariantClosure};
+use crate::control_flow::pattern::{self, PATTERN};
+use crate::registry::{AlgorithmRunner, InputRegion, Parameter, Params, SizeSpace, VariantCheck, VariantClosure, VariantDescriptor};
+use crate::utils::SeededRng;
 use std::sync::Arc;
 
-/// Generate test data - random opcodes (0-7) and values
-fn generate_test_data(size: usize, seed: u64) -> Vec<(u8, u32)> {
-    let mut data = Vec::with_capacity(size);
-    let mut rng = seed;
-
-    for _ in 0..size {
-        rng = rng.wrapping_mul(6364136223846793005).wrapping_add(1);
-        let opcode = ((rng >> 32) % 8) as u8;
-        let value = ((rng >> 40) % 1000) as u32 + 1;
-        data.push((opcode, value));
-    }
-    data
+/// Generate test data - opcodes (0-7) following `pattern`, and random values
+fn generate_test_data(size: usize, pattern: &str, seed: u64) -> Vec<(u8, u32)> {
+    let mut rng = SeededRng::new(seed);
+    let opcodes = pattern::generate(pattern, size, 8, &mut rng);
+    opcodes
+        .into_iter()
+        .map(|opcode| (opcode as u8, rng.next_u32_range(1000) + 1))
+        .collect()
 }
 
 pub struct ElseIfVsJumpTableRunner;
@@ -35,30 +33,43 @@ impl AlgorithmRunner for ElseIfVsJumpTableRunner {
         "Comparison between else-if chains and jump tables"
     }
 
-    fn available_variants(&self) -> Vec<&'static str> {
-        code::get_variants().iter().map(|v| v.name).collect()
+    fn size_space(&self) -> SizeSpace {
+        // Number of inputs cycled through: short cycles are learnt by the branch
+        // predictor, long ones are not
+        SizeSpace::Defaults(&[64, 16384])
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![PATTERN]
+    }
+
+    fn variants(&self) -> Vec<VariantDescriptor> {
+        code::get_variants().iter().map(|v| v.descriptor()).collect()
     }
 
-    fn get_variant_closures<'a>(&'a self, size: usize) -> Vec<VariantClosure<'a>> {
-        let data: Arc<Vec<(u8, u32)>> = Arc::new(generate_test_data(size, 0x87654321));
+    fn get_variant_closures<'a>(&'a self, size: usize, params: &Params, seed: u64) -> Vec<VariantClosure<'a>> {
+        let pattern = params.get(PATTERN.name).unwrap_or(PATTERN.values[0]);
+        let data: Arc<Vec<(u8, u32)>> = Arc::new(generate_test_data(size, pattern, seed));
 
         code::get_variants()
             .into_iter()
             .map(|v| {
                 let data = Arc::clone(&data);
                 let func = v.function;
+                let mut idx = 0;
 
                 VariantClosure {
                     name: v.name,
                     description: v.description,
+                    tags: v.tags,
+                    inputs: vec![InputRegion::of(&data)],
                     run: Box::new(move || {
-                        // Timing inside closure - measures entire loop
-                        let (elapsed, _) = crate::measure!({
-                            let mut last_result = 0u32;
-                            for &(op, val) in data.iter() {
-                                last_result = std::hint::black_box(func(std::hint::black_box(op), std::hint::black_box(val)));
-                            }
-                            last_result
+                        // One dispatch per iteration, cycling through the data: the runner
+                        // chooses how many calls are timed together
+                        let (elapsed, _) = crate::measure_batched!({
+                            let (op, val) = data[idx];
+                            idx = if idx + 1 == data.len() { 0 } else { idx + 1 };
+                            func(std::hint::black_box(op), std::hint::black_box(val))
                         });
                         (elapsed, None) // No precision measurement for control flow
                     }),
@@ -67,7 +78,7 @@ impl AlgorithmRunner for ElseIfVsJumpTableRunner {
             .collect()
     }
 
-    fn verify(&self) -> Result<(), String> {
-        test::verify_all()
+    fn verify_variants(&self) -> Vec<VariantCheck> {
+        test::verify_variants()
     }
 }
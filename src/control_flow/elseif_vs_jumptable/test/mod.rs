@@ -1,9 +1,10 @@
 //! Tests for else-if vs jump table implementations
 
 use super::code::{get_variants, original};
+use crate::registry::{first_failure, VariantCheck};
 
-/// Verify all variants produce the same results as the original
-pub fn verify_all() -> Result<(), String> {
+/// Check every variant against the original, one entry per variant
+pub fn verify_variants() -> Vec<VariantCheck> {
     let test_cases: Vec<(u8, u32)> = vec![
         (0, 1),
         (0, 100),
@@ -25,25 +26,29 @@ pub fn verify_all() -> Result<(), String> {
         (255, 100), // Invalid
     ];
 
-    for variant in get_variants() {
-        if variant.name == "original" {
-            continue;
-        }
+    get_variants()
+        .into_iter()
+        .filter(|v| v.name != "original")
+        .map(|variant| {
+            for &(opcode, value) in &test_cases {
+                let expected = original::dispatch_operation(opcode, value);
+                let actual = (variant.function)(opcode, value);
 
-        for &(opcode, value) in &test_cases {
-            let expected = original::dispatch_operation(opcode, value);
-            let actual = (variant.function)(opcode, value);
-
-            if actual != expected {
-                return Err(format!(
-                    "Variant '{}' failed for opcode={}, value={}: expected {}, got {}",
-                    variant.name, opcode, value, expected, actual
-                ));
+                if actual != expected {
+                    return VariantCheck::failed(
+                        variant.name,
+                        format!("Failed for opcode={}, value={}: expected {}, got {}", opcode, value, expected, actual),
+                    );
+                }
             }
-        }
-    }
+            VariantCheck::passed(variant.name)
+        })
+        .collect()
+}
 
-    Ok(())
+/// Verify all variants produce the same results as the original
+pub fn verify_all() -> Result<(), String> {
+    first_failure(&verify_variants())
 }
 
 #[cfg(test)]
@@ -1,2 +1,3 @@
 pub mod call_vs_branch;
 pub mod elseif_vs_jumptable;
+pub mod pattern;
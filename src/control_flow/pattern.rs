@@ -0,0 +1,93 @@
+//! Input patterns for the control-flow benchmarks.
+//!
+//! How well the branch predictor learns a sequence of dispatched values decides
+//! whether branches, jump tables or branchless code win. The `pattern`
+//! parameter selects the order of the values, from trivially predictable
+//! (`constant`) to unpredictable (`random`).
+
+use crate::registry::Parameter;
+use crate::utils::SeededRng;
+
+/// Order of the values an algorithm dispatches on
+pub const PATTERN: Parameter = Parameter {
+    name: "pattern",
+    description: "Value order: random, constant, sorted, periodic-K (K random values repeated), zipf (skewed)",
+    values: &["random", "constant", "sorted", "periodic-4", "periodic-16", "zipf"],
+};
+
+/// Generate `size` values in `0..symbols` following `pattern`:
+///
+/// - `random`: uniformly distributed
+/// - `constant`: always 0 (the first case of an else-if chain)
+/// - `sorted`: uniformly distributed, in ascending order (long runs of each value)
+/// - `periodic-K`: a random sequence of K values, repeated
+/// - `zipf`: value k with probability proportional to 1/(k+1)
+///
+/// Unknown patterns generate `random` values.
+pub fn generate(pattern: &str, size: usize, symbols: u32, rng: &mut SeededRng) -> Vec<u32> {
+    match pattern {
+        "constant" => vec![0; size],
+        "sorted" => {
+            let mut values = uniform(size, symbols, rng);
+            values.sort_unstable();
+            values
+        }
+        "zipf" => zipf(size, symbols, rng),
+        _ => match pattern.strip_prefix("periodic-").and_then(|k| k.parse::<usize>().ok()) {
+            Some(period) if period > 0 => {
+                let cycle = uniform(period, symbols, rng);
+                cycle.iter().copied().cycle().take(size).collect()
+            }
+            _ => uniform(size, symbols, rng),
+        },
+    }
+}
+
+fn uniform(size: usize, symbols: u32, rng: &mut SeededRng) -> Vec<u32> {
+    (0..size).map(|_| rng.next_u32_range(symbols)).collect()
+}
+
+/// Zipf distribution with exponent 1, sampled by inverting the cumulative weights
+fn zipf(size: usize, symbols: u32, rng: &mut SeededRng) -> Vec<u32> {
+    let mut cumulative = Vec::with_capacity(symbols as usize);
+    let mut total = 0.0;
+    for k in 0..symbols {
+        total += 1.0 / (k as f64 + 1.0);
+        cumulative.push(total);
+    }
+    (0..size)
+        .map(|_| {
+            let u = (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64 * total;
+            cumulative.partition_point(|&c| c <= u).min(symbols as usize - 1) as u32
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns() {
+        let mut rng = SeededRng::new(7);
+        for pattern in PATTERN.values {
+            let values = generate(pattern, 1000, 8, &mut rng);
+            assert_eq!(values.len(), 1000, "{}", pattern);
+            assert!(values.iter().all(|&v| v < 8), "{}", pattern);
+        }
+
+        assert!(generate("constant", 10, 8, &mut rng).iter().all(|&v| v == 0));
+        assert!(generate("sorted", 100, 8, &mut rng).windows(2).all(|w| w[0] <= w[1]));
+
+        let periodic = generate("periodic-4", 12, 8, &mut rng);
+        assert_eq!(periodic[..4], periodic[4..8]);
+        assert_eq!(periodic[..4], periodic[8..]);
+
+        // Skewed towards small values: 0 is the most frequent of 8 (about 37%)
+        let zipf = generate("zipf", 10_000, 8, &mut rng);
+        let zeros = zipf.iter().filter(|&&v| v == 0).count();
+        let sevens = zipf.iter().filter(|&&v| v == 7).count();
+        assert!(zeros > 3000 && zeros < 4400, "{}", zeros);
+        assert!(sevens < zeros / 4, "{}", sevens);
+    }
+}
@@ -12,10 +12,15 @@ pub mod utils;
 pub use utils::tui;
 
 /// Re-export run_benchmarks from utils::runner
-pub use utils::runner::run_benchmarks;
+pub use utils::runner::{run_benchmarks, BenchmarkOptions};
 
 /// Re-export PinStrategy from utils::timer
-pub use utils::timer::PinStrategy;
+pub use utils::timer::{Batching, PinStrategy, TimingConfig};
+
+/// Re-export variant tags and the variant filter
+pub use utils::filter::VariantFilter;
+pub use utils::params::{Parameter, Params};
+pub use utils::tags::{Language, VariantTags};
 
 /// Re-export commonly used items
 pub mod prelude {
@@ -46,4 +51,19 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_required_features_are_known() {
+        // An unknown name is never supported, so a typo would silently skip the variant
+        use crate::utils::cpu_features::KNOWN_FEATURES;
+
+        let registry = build_registry();
+        for algo in registry.all() {
+            let required = algo.variants().into_iter().flat_map(|v| v.required_features.iter().copied());
+            let missing = algo.skipped_variants().into_iter().flat_map(|s| s.missing_features);
+            for feature in required.chain(missing) {
+                assert!(KNOWN_FEATURES.contains(&feature), "{}: unknown CPU feature '{}'", algo.name(), feature);
+            }
+        }
+    }
 }
@@ -8,7 +8,7 @@ mod scalar_opt;
 #[cfg(target_arch = "x86_64")]
 mod x86_64_sse2;
 
-#[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+#[cfg(target_arch = "x86_64")]
 mod x86_64_avx2;
 
 #[cfg(target_arch = "x86_64")]
@@ -19,7 +19,7 @@ pub use scalar_opt::dot_product_scalar_opt;
 #[cfg(target_arch = "x86_64")]
 pub use x86_64_sse2::dot_product_x86_64_sse2;
 
-#[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+#[cfg(target_arch = "x86_64")]
 pub use x86_64_avx2::dot_product_x86_64_avx2;
 
 /// Trait for dot product implementations
@@ -31,23 +31,37 @@ pub trait DotProduct {
     fn name() -> &'static str;
 }
 
-use crate::utils::VariantInfo;
+use crate::utils::{split_supported, SkippedVariant, VariantInfo, VariantTags};
 
 /// Type alias for dot product function signature
 pub type DotProductFn = fn(&[f32], &[f32]) -> f32;
 
 /// Get all available variants for the current CPU
 pub fn available_variants() -> Vec<VariantInfo<DotProductFn>> {
+    split_supported(compiled_variants()).0
+}
+
+/// Variants compiled for this target that the current CPU cannot run
+pub fn skipped_variants() -> Vec<SkippedVariant> {
+    split_supported(compiled_variants()).1
+}
+
+/// All variants compiled for this target, whether or not the CPU supports them
+fn compiled_variants() -> Vec<VariantInfo<DotProductFn>> {
     let mut variants: Vec<VariantInfo<DotProductFn>> = vec![
         VariantInfo {
             name: "original",
             description: "Clean, idiomatic Rust reference implementation",
             function: dot_product_original,
+            required_features: &[],
+            tags: VariantTags::rust(),
         },
         VariantInfo {
             name: "scalar_opt",
             description: "Optimized scalar implementation (manual loop unrolling)",
             function: dot_product_scalar_opt,
+            required_features: &[],
+            tags: VariantTags::rust(),
         },
     ];
 
@@ -57,15 +71,19 @@ pub fn available_variants() -> Vec<VariantInfo<DotProductFn>> {
             name: "x86_64-sse2",
             description: "x86_64 with SSE2 SIMD intrinsics",
             function: dot_product_x86_64_sse2,
+            required_features: &["sse2"],
+            tags: VariantTags::rust().arch("x86_64").simd("sse2"),
         });
     }
 
-    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+    #[cfg(target_arch = "x86_64")]
     {
         variants.push(VariantInfo {
             name: "x86_64-avx2",
-            description: "x86_64 with AVX2 SIMD intrinsics",
+            description: "x86_64 with AVX2 SIMD intrinsics and FMA",
             function: dot_product_x86_64_avx2,
+            required_features: &["avx2", "fma"],
+            tags: VariantTags::rust().arch("x86_64").simd("avx2"),
         });
     }
 
@@ -77,17 +95,23 @@ pub fn available_variants() -> Vec<VariantInfo<DotProductFn>> {
             name: "c-original",
             description: "C reference implementation",
             function: dot_product_c_original,
+            required_features: &[],
+            tags: VariantTags::c(),
         });
         variants.push(VariantInfo {
             name: "c-scalar_opt",
             description: "C optimized scalar implementation",
             function: dot_product_c_scalar_opt,
+            required_features: &[],
+            tags: VariantTags::c(),
         });
         #[cfg(target_arch = "x86_64")]
         variants.push(VariantInfo {
             name: "c-x86_64-sse2",
             description: "C with SSE2 SIMD intrinsics",
             function: dot_product_c_x86_64_sse2,
+            required_features: &["sse2"],
+            tags: VariantTags::c().arch("x86_64").simd("sse2"),
         });
     }
 
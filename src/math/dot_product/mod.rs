@@ -10,10 +10,46 @@ pub mod test;
 
 pub use code::*;
 
-use crate::registry::{AlgorithmRunner, VariantClosure};
-use rand::Rng;
+use crate::registry::{
+    AlgorithmRunner, InputRegion, Parameter, Params, SkippedVariant, VariantCheck, VariantClosure, VariantDescriptor,
+    Work,
+};
+use crate::utils::SeededRng;
+use std::ops::Range;
 use std::sync::Arc;
 
+/// Seed of the verification inputs (fixed so that failures can be reproduced)
+const VERIFY_SEED: u64 = 0x0D07_9A0D;
+
+/// Generate two random vectors with values in [-1.0, 1.0)
+fn generate_vectors(size: usize, seed: u64) -> (Vec<f32>, Vec<f32>) {
+    let mut rng = SeededRng::new(seed);
+    let a = (0..size).map(|_| rng.next_f32_range()).collect();
+    let b = (0..size).map(|_| rng.next_f32_range()).collect();
+    (a, b)
+}
+
+/// Byte offset of both vectors from a cache-line boundary (multiple of 4)
+const OFFSET: Parameter = Parameter {
+    name: "offset",
+    description: "Byte offset of both vectors from a 64-byte boundary",
+    values: &["0", "4"],
+};
+
+/// Cache line size the offset is relative to, in elements
+const LINE_ELEMENTS: usize = 64 / std::mem::size_of::<f32>();
+
+/// Copy `data` into a new buffer, `offset` bytes past a 64-byte boundary.
+/// Returns the buffer and the range of the copy within it.
+fn place_at_offset(data: &[f32], offset: usize) -> (Vec<f32>, Range<usize>) {
+    let shift = offset / std::mem::size_of::<f32>();
+    let mut buffer = vec![0.0f32; data.len() + LINE_ELEMENTS + shift];
+    let misalignment = (buffer.as_ptr() as usize % 64) / std::mem::size_of::<f32>();
+    let start = (LINE_ELEMENTS - misalignment) % LINE_ELEMENTS + shift;
+    buffer[start..start + data.len()].copy_from_slice(data);
+    (buffer, start..start + data.len())
+}
+
 /// Runner for the dot product algorithm
 pub struct DotProductRunner;
 
@@ -30,29 +66,49 @@ impl AlgorithmRunner for DotProductRunner {
         "math"
     }
 
-    fn available_variants(&self) -> Vec<&'static str> {
-        code::available_variants().iter().map(|v| v.name).collect()
+    fn variants(&self) -> Vec<VariantDescriptor> {
+        code::available_variants().iter().map(|v| v.descriptor()).collect()
     }
 
-    fn get_variant_closures<'a>(&'a self, size: usize) -> Vec<VariantClosure<'a>> {
-        // Generate test data
-        let mut rng = rand::rng();
-        let a: Arc<Vec<f32>> = Arc::new((0..size).map(|_| rng.random_range(-1.0..1.0)).collect());
-        let b: Arc<Vec<f32>> = Arc::new((0..size).map(|_| rng.random_range(-1.0..1.0)).collect());
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![OFFSET]
+    }
+
+    fn work(&self, size: usize, _params: &Params) -> Vec<Work> {
+        // Both vectors are read once
+        vec![Work::bytes(2 * size * std::mem::size_of::<f32>()), Work::elements(size)]
+    }
+
+    fn skipped_variants(&self) -> Vec<SkippedVariant> {
+        code::skipped_variants()
+    }
+
+    fn get_variant_closures<'a>(&'a self, size: usize, params: &Params, seed: u64) -> Vec<VariantClosure<'a>> {
+        let offset = params.get(OFFSET.name).unwrap_or(OFFSET.values[0]).parse().unwrap_or(0);
+        let (a, b) = generate_vectors(size, seed);
+        // Each buffer has its own alignment, hence its own range
+        let (a, a_range) = place_at_offset(&a, offset);
+        let (b, b_range) = place_at_offset(&b, offset);
+        let a: Arc<Vec<f32>> = Arc::new(a);
+        let b: Arc<Vec<f32>> = Arc::new(b);
 
         code::available_variants()
             .into_iter()
             .map(|v| {
                 let a = Arc::clone(&a);
                 let b = Arc::clone(&b);
+                let (a_range, b_range) = (a_range.clone(), b_range.clone());
                 let func = v.function;
 
                 VariantClosure {
                     name: v.name,
                     description: v.description,
+                    tags: v.tags,
+                    inputs: vec![InputRegion::of(&a[a_range.clone()]), InputRegion::of(&b[b_range.clone()])],
                     run: Box::new(move || {
+                        let (a, b) = (&a[a_range.clone()], &b[b_range.clone()]);
                         // Timing inside closure eliminates Fn trait overhead
-                        let (elapsed, result) = crate::measure!(func(&a, &b));
+                        let (elapsed, result) = crate::measure!(func(a, b));
                         (elapsed, Some(result as f64))
                     }),
                 }
@@ -60,36 +116,32 @@ impl AlgorithmRunner for DotProductRunner {
             .collect()
     }
 
-    fn verify(&self) -> Result<(), String> {
-        let mut rng = rand::rng();
-        let size = 1023;
-        let a: Vec<f32> = (0..size).map(|_| rng.random_range(-1.0..1.0)).collect();
-        let b: Vec<f32> = (0..size).map(|_| rng.random_range(-1.0..1.0)).collect();
+    fn verify_variants(&self) -> Vec<VariantCheck> {
+        let (a, b) = generate_vectors(1023, VERIFY_SEED);
 
         let variants = code::available_variants();
-        let original_variant = variants
-            .iter()
-            .find(|v| v.name == "original")
-            .ok_or("No 'original' variant found for reference")?;
+        let Some(original_variant) = variants.iter().find(|v| v.name == "original") else {
+            return vec![VariantCheck::failed("original", "No 'original' variant found for reference".to_string())];
+        };
 
         let expected = (original_variant.function)(&a, &b);
 
-        for variant in &variants {
-            if variant.name == "original" {
-                continue;
-            }
-
-            let result = (variant.function)(&a, &b);
-            let diff = (result - expected).abs();
-
-            if diff > 1e-4 {
-                return Err(format!(
-                    "Variant '{}' failed verification. Expected {}, got {}, diff {}",
-                    variant.name, expected, result, diff
-                ));
-            }
-        }
-
-        Ok(())
+        variants
+            .iter()
+            .filter(|v| v.name != "original")
+            .map(|variant| {
+                let result = (variant.function)(&a, &b);
+                let diff = (result - expected).abs();
+
+                if diff > 1e-4 {
+                    VariantCheck::failed(
+                        variant.name,
+                        format!("Expected {}, got {}, diff {}", expected, result, diff),
+                    )
+                } else {
+                    VariantCheck::passed(variant.name)
+                }
+            })
+            .collect()
     }
 }
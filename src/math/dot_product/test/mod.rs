@@ -43,5 +43,37 @@ mod tests {
         assert_close(result, 12.0, "original single");
     }
 
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn test_avx2_matches_original() {
+        if !(is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma")) {
+            return;
+        }
+        let a: Vec<f32> = (0..37).map(|i| i as f32 * 0.25).collect();
+        let b: Vec<f32> = (0..37).map(|i| 1.0 - i as f32 * 0.125).collect();
+        let expected = dot_product_original(&a, &b);
+        let result = dot_product_x86_64_avx2(&a, &b);
+        assert!((result - expected).abs() <= expected.abs() * 1e-5, "avx2: {} vs {}", result, expected);
+    }
+
+    #[test]
+    fn test_skipped_variants_are_not_available() {
+        let available: Vec<&str> = available_variants().iter().map(|v| v.name).collect();
+        for skipped in skipped_variants() {
+            assert!(!available.contains(&skipped.name));
+            assert!(skipped.reason.starts_with("CPU lacks"));
+        }
+    }
+
+    #[test]
+    fn test_place_at_offset() {
+        let data: Vec<f32> = (0..10).map(|i| i as f32).collect();
+        for offset in [0, 4, 16] {
+            let (buffer, range) = crate::math::dot_product::place_at_offset(&data, offset);
+            assert_eq!(&buffer[range.clone()], data.as_slice());
+            assert_eq!(buffer[range].as_ptr() as usize % 64, offset);
+        }
+    }
+
     // Variant testing is now handled by the generic verify() method via the Registry.
 }
@@ -8,7 +8,7 @@ pub use x86_64_asm::xoroshiro_x86_64_asm;
 
 pub mod c_impl;
 
-use crate::utils::VariantInfo;
+use crate::utils::{VariantInfo, VariantTags};
 
 /// Type alias for xoroshiro function signature
 pub type XoroshiroFn = fn(&mut u64, &mut u64) -> u64;
@@ -17,6 +17,8 @@ pub fn available_variants() -> Vec<VariantInfo<XoroshiroFn>> {
     let mut variants: Vec<VariantInfo<XoroshiroFn>> = vec![VariantInfo {
         name: "original",
         function: original::xoroshiro_original,
+        required_features: &[],
+        tags: VariantTags::rust(),
         description: "Original pure Rust implementation",
     }];
 
@@ -24,6 +26,8 @@ pub fn available_variants() -> Vec<VariantInfo<XoroshiroFn>> {
     variants.push(VariantInfo {
         name: "x86_64-asm",
         function: x86_64_asm::xoroshiro_x86_64_asm,
+        required_features: &[],
+        tags: VariantTags::rust().arch("x86_64").technique("asm"),
         description: "Hand-written x86_64 assembly",
     });
 
@@ -31,6 +35,8 @@ pub fn available_variants() -> Vec<VariantInfo<XoroshiroFn>> {
         variants.push(VariantInfo {
             name: "c-original",
             function: c_impl::xoroshiro_c_wrapper,
+            required_features: &[],
+            tags: VariantTags::c(),
             description: "C implementation of Xoroshiro128++",
         });
     }
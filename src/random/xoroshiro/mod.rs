@@ -2,7 +2,8 @@ pub mod code;
 #[cfg(test)]
 pub mod test;
 
-use crate::registry::{AlgorithmRunner, VariantClosure};
+use crate::registry::{AlgorithmRunner, Params, SizeSpace, VariantCheck, VariantClosure, VariantDescriptor};
+use crate::utils::SeededRng;
 
 pub struct XoroshiroRunner;
 
@@ -19,31 +20,35 @@ impl AlgorithmRunner for XoroshiroRunner {
         "random"
     }
 
-    fn available_variants(&self) -> Vec<&'static str> {
-        code::available_variants().iter().map(|v| v.name).collect()
+    fn variants(&self) -> Vec<VariantDescriptor> {
+        code::available_variants().iter().map(|v| v.descriptor()).collect()
     }
 
-    fn get_variant_closures<'a>(&'a self, size: usize) -> Vec<VariantClosure<'a>> {
-        // Only run for the smallest size to avoid redundant measurements
-        // Since we measure a single function call, size is irrelevant
-        if size != 64 {
-            return Vec::new();
-        }
+    fn size_space(&self) -> SizeSpace {
+        // A single call per iteration: there is no input size
+        SizeSpace::Independent
+    }
 
+    fn get_variant_closures<'a>(&'a self, _size: usize, _params: &Params, seed: u64) -> Vec<VariantClosure<'a>> {
         code::available_variants()
             .into_iter()
             .map(|v| {
                 let func = v.function;
-                // Use mutable captures directly - FnMut allows this
-                let mut s0 = 0x12345678u64;
-                let mut s1 = 0x87654321u64;
+                // Use mutable captures directly - FnMut allows this.
+                // Every variant starts from the same seeded state (never all zero).
+                let mut s0 = seed;
+                let mut s1 = SeededRng::new(seed).next_u64() | 1;
 
                 VariantClosure {
                     name: v.name,
                     description: v.description,
+                    tags: v.tags,
+                    // The generator state lives in registers
+                    inputs: Vec::new(),
                     run: Box::new(move || {
-                        // Timing inside closure eliminates Fn trait overhead
-                        let (elapsed, result) = crate::measure!(func(&mut s0, &mut s1));
+                        // A single call is far below the timer resolution: the runner
+                        // chooses how many calls are timed together
+                        let (elapsed, result) = crate::measure_batched!(func(&mut s0, &mut s1));
                         (elapsed, Some(result as f64))
                     }),
                 }
@@ -51,13 +56,12 @@ impl AlgorithmRunner for XoroshiroRunner {
             .collect()
     }
 
-    fn verify(&self) -> Result<(), String> {
+    fn verify_variants(&self) -> Vec<VariantCheck> {
         let variants = code::available_variants();
 
-        let original_variant = variants
-            .iter()
-            .find(|v| v.name == "original")
-            .ok_or("No 'original' variant found for reference")?;
+        let Some(original_variant) = variants.iter().find(|v| v.name == "original") else {
+            return vec![VariantCheck::failed("original", "No 'original' variant found for reference".to_string())];
+        };
 
         let seed_lo_ref = 0xdeadbeef;
         let seed_hi_ref = 0xcafebab;
@@ -69,25 +73,24 @@ impl AlgorithmRunner for XoroshiroRunner {
             expected_sequence.push((original_variant.function)(&mut s0, &mut s1));
         }
 
-        for variant in &variants {
-            if variant.name == "original" {
-                continue;
-            }
-
-            let mut s0 = seed_lo_ref;
-            let mut s1 = seed_hi_ref;
-
-            for (i, &expected) in expected_sequence.iter().enumerate() {
-                let result = (variant.function)(&mut s0, &mut s1);
-                if result != expected {
-                    return Err(format!(
-                        "Variant '{}' failed verification at iteration {}. Expected {}, got {}",
-                        variant.name, i, expected, result
-                    ));
+        variants
+            .iter()
+            .filter(|v| v.name != "original")
+            .map(|variant| {
+                let mut s0 = seed_lo_ref;
+                let mut s1 = seed_hi_ref;
+
+                for (i, &expected) in expected_sequence.iter().enumerate() {
+                    let result = (variant.function)(&mut s0, &mut s1);
+                    if result != expected {
+                        return VariantCheck::failed(
+                            variant.name,
+                            format!("Mismatch at iteration {}. Expected {}, got {}", i, expected, result),
+                        );
+                    }
                 }
-            }
-        }
-
-        Ok(())
+                VariantCheck::passed(variant.name)
+            })
+            .collect()
     }
 }
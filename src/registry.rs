@@ -4,7 +4,9 @@
 //! algorithms without needing separate binary files for each.
 
 use crate::utils::bench::Measurement;
+pub use crate::utils::cache::InputRegion;
 use crate::utils::timer::VariantResult;
+pub use crate::utils::{Parameter, Params, SkippedVariant, VariantDescriptor, VariantTags, Work, WorkUnit};
 
 /// Result from running a variant benchmark (alias for VariantResult)
 pub type BenchmarkResult = VariantResult;
@@ -13,11 +15,58 @@ pub type BenchmarkResult = VariantResult;
 pub struct VariantClosure<'a> {
     pub name: &'static str,
     pub description: &'static str,
+    /// Language, architecture, SIMD level and technique
+    pub tags: VariantTags,
+    /// Memory the closure reads, flushed before each sample with `--cache cold-data`
+    pub inputs: Vec<InputRegion>,
     /// Returns (timing_measurement, optional_result_value).
     /// Timing happens inside the closure to eliminate Fn trait overhead.
     pub run: Box<dyn FnMut() -> (Measurement, Option<f64>) + 'a>,
 }
 
+/// Input sizes an algorithm is meaningfully benchmarked at
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SizeSpace {
+    /// Any size: `--sizes`, or the runner's `DEFAULT_SIZES`
+    Any,
+    /// Algorithm-specific default sizes (`--sizes` still overrides them)
+    Defaults(&'static [usize]),
+    /// The measured cost does not depend on an input size: benchmarked once,
+    /// with size `SIZE_INDEPENDENT`
+    Independent,
+}
+
+/// Size passed to (and reported for) size-independent algorithms
+pub const SIZE_INDEPENDENT: usize = 0;
+
+/// Outcome of verifying one variant against the reference implementation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VariantCheck {
+    pub name: &'static str,
+    /// `Err` describes the first mismatch
+    pub result: Result<(), String>,
+}
+
+impl VariantCheck {
+    /// The variant matched the reference
+    pub fn passed(name: &'static str) -> Self {
+        Self { name, result: Ok(()) }
+    }
+
+    /// The variant differs from the reference (or could not be checked)
+    pub fn failed(name: &'static str, reason: String) -> Self {
+        Self { name, result: Err(reason) }
+    }
+}
+
+/// First failure of a verification report, as a single error
+pub fn first_failure(checks: &[VariantCheck]) -> Result<(), String> {
+    match checks.iter().find_map(|c| c.result.as_ref().err().map(|e| (c.name, e))) {
+        Some((name, reason)) => Err(format!("Variant '{}' failed verification: {}", name, reason)),
+        None => Ok(()),
+    }
+}
+
 /// Trait that all algorithm benchmarkers must implement
 pub trait AlgorithmRunner: Send + Sync {
     /// Name of the algorithm (e.g., "dot_product")
@@ -29,16 +78,53 @@ pub trait AlgorithmRunner: Send + Sync {
     /// Category (e.g., "math", "sorting")
     fn category(&self) -> &'static str;
 
+    /// Variants runnable on this CPU, with their description and tags
+    fn variants(&self) -> Vec<VariantDescriptor>;
+
     /// Get list of available variant names
-    fn available_variants(&self) -> Vec<&'static str>;
+    fn available_variants(&self) -> Vec<&'static str> {
+        self.variants().iter().map(|v| v.name).collect()
+    }
+
+    /// Input sizes the algorithm is benchmarked at by default
+    fn size_space(&self) -> SizeSpace {
+        SizeSpace::Any
+    }
+
+    /// Parameters besides the input size, benchmarked in every combination
+    /// of their values (none by default)
+    fn parameters(&self) -> Vec<Parameter> {
+        Vec::new()
+    }
+
+    /// Work done by one call at `size` with `params` (bytes read, elements
+    /// processed, operations), reported as throughput (none by default)
+    fn work(&self, _size: usize, _params: &Params) -> Vec<Work> {
+        Vec::new()
+    }
+
+    /// Variants compiled in but not runnable on this CPU (missing features), with the reason
+    fn skipped_variants(&self) -> Vec<SkippedVariant> {
+        Vec::new()
+    }
 
     /// Get closures for each variant, ready to be measured.
     /// Each closure does ONE execution and returns a result value.
     /// The runner will handle warmup, timing, and repetition.
-    fn get_variant_closures<'a>(&'a self, size: usize) -> Vec<VariantClosure<'a>>;
+    ///
+    /// `params` holds one value of each parameter declared by `parameters`.
+    /// All input data must be generated from `seed` (derived from the global
+    /// `--seed` with `bench::derive_seed`), so that runs are reproducible.
+    fn get_variant_closures<'a>(&'a self, size: usize, params: &Params, seed: u64) -> Vec<VariantClosure<'a>>;
+
+    /// Check every variant against the reference implementation ("original"),
+    /// one entry per variant other than the reference
+    fn verify_variants(&self) -> Vec<VariantCheck>;
 
     /// Verify correctness of all variants against the reference
-    fn verify(&self) -> Result<(), String>;
+    fn verify(&self) -> Result<(), String> {
+        first_failure(&self.verify_variants())
+    }
 }
 
 /// Global registry of all algorithms
@@ -85,6 +171,68 @@ impl AlgorithmRegistry {
             .map(|a| a.as_ref())
             .collect()
     }
+
+    /// List categories, in registration order without duplicates
+    pub fn categories(&self) -> Vec<&'static str> {
+        let mut categories: Vec<&'static str> = Vec::new();
+        for algo in &self.algorithms {
+            if !categories.contains(&algo.category()) {
+                categories.push(algo.category());
+            }
+        }
+        categories
+    }
+
+    /// Closest algorithm name to a misspelled one ("did you mean ...?")
+    pub fn suggest(&self, name: &str) -> Option<&'static str> {
+        closest_match(name, &self.list_names())
+    }
+}
+
+/// Shortest input (or candidate) that counts as a match by containment alone
+const MIN_CONTAINED_LEN: usize = 3;
+
+/// Closest candidate to `input`: a candidate containing it (or contained in it),
+/// otherwise the one with the smallest edit distance if that is small enough.
+/// Containment only counts for at least `MIN_CONTAINED_LEN` characters, so a
+/// single letter does not "match" every name containing it.
+pub fn closest_match<'a>(input: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let input = input.to_lowercase();
+    if input.is_empty() {
+        return None;
+    }
+    let contained = |part: &str, whole: &str| part.chars().count() >= MIN_CONTAINED_LEN && whole.contains(part);
+    let max_distance = (input.chars().count() / 3).max(2);
+    candidates
+        .iter()
+        .map(|&candidate| {
+            let lower = candidate.to_lowercase();
+            let distance = if contained(&input, &lower) || contained(&lower, &input) {
+                0
+            } else {
+                edit_distance(&input, &lower)
+            };
+            (distance, candidate)
+        })
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance (insertions, deletions and substitutions)
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
 }
 
 impl Default for AlgorithmRegistry {
@@ -105,3 +253,28 @@ pub fn build_registry() -> AlgorithmRegistry {
 
     registry
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("dot_product", "dot_product"), 0);
+    }
+
+    #[test]
+    fn test_suggest() {
+        let registry = build_registry();
+        assert_eq!(registry.suggest("dot_prodcut"), Some("dot_product"));
+        assert_eq!(registry.suggest("xoroshiro"), Some("xoroshiro128++"));
+        assert_eq!(registry.suggest("Call_vs_Branch"), Some("call_vs_branch"));
+        assert_eq!(registry.suggest("quicksort"), None);
+        assert_eq!(closest_match("rando", &registry.categories()), Some("random"));
+        assert_eq!(closest_match("", &registry.list_names()), None);
+        assert_eq!(closest_match("o", &["original", "scalar_opt"]), None);
+        assert_eq!(closest_match("ori", &["original", "scalar_opt"]), Some("original"));
+    }
+}
@@ -1,117 +1,109 @@
 //! Measurement primitives and utilities.
 //!
-//! This module provides low-level measurement primitives that work with both
-//! CPU cycles and wall-clock time depending on feature flags.
-//!
-//! By default (`cpu_cycles` feature), measurements use CPU cycle counters
-//! for precise micro-benchmarking. Use `--features use_time` or
-//! `--no-default-features` to use wall-clock time instead.
+//! This module provides low-level measurement primitives on top of the clock
+//! selected at run time (see `clock`): timestamp counter cycles by default,
+//! nanoseconds with `--clock monotonic`, core cycles with `--clock perf`.
 
+use std::cell::Cell;
 use std::time::Duration;
 
-/// Measurement value type - cycles (u64) or Duration depending on feature
-#[cfg(all(feature = "cpu_cycles", not(feature = "use_time")))]
-pub type Measurement = u64;
+use super::clock;
 
-#[cfg(any(not(feature = "cpu_cycles"), feature = "use_time"))]
-pub type Measurement = Duration;
+/// Measurement value type: readings of the selected clock (see `unit_name`)
+pub type Measurement = u64;
 
-/// Read current measurement (cycles or time)
-#[cfg(all(feature = "cpu_cycles", not(feature = "use_time")))]
+/// Read the selected clock
 #[inline(always)]
 pub fn now() -> Measurement {
-    crate::utils::cycles::read_cycles()
-}
-
-#[cfg(any(not(feature = "cpu_cycles"), feature = "use_time"))]
-#[inline(always)]
-pub fn now() -> std::time::Instant {
-    std::time::Instant::now()
+    clock::read()
 }
 
 /// Calculate elapsed measurement
-#[cfg(all(feature = "cpu_cycles", not(feature = "use_time")))]
 #[inline(always)]
 pub fn elapsed(start: Measurement) -> Measurement {
-    crate::utils::cycles::read_cycles().saturating_sub(start)
+    clock::read().saturating_sub(start)
 }
 
-#[cfg(any(not(feature = "cpu_cycles"), feature = "use_time"))]
-#[inline(always)]
-pub fn elapsed(start: std::time::Instant) -> Measurement {
-    start.elapsed()
+/// Get the measurement unit name of the selected clock
+pub fn unit_name() -> &'static str {
+    clock::selected().unit()
 }
 
-/// Convert measurement to nanoseconds for display
-#[cfg(all(feature = "cpu_cycles", not(feature = "use_time")))]
-pub fn to_nanos(m: Measurement) -> u64 {
-    m
+/// Whether measurements are counter ticks rather than nanoseconds
+pub fn measures_cycles() -> bool {
+    !clock::selected().is_wall_clock()
 }
 
-#[cfg(any(not(feature = "cpu_cycles"), feature = "use_time"))]
-pub fn to_nanos(m: Measurement) -> u64 {
-    m.as_nanos() as u64
+/// Frequency of the timestamp counter in Hz, calibrated on first use
+/// (see `cycles::calibrate_frequency`)
+#[cfg(any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64"))]
+pub fn counter_frequency() -> Option<f64> {
+    static FREQUENCY: std::sync::OnceLock<Option<f64>> = std::sync::OnceLock::new();
+    *FREQUENCY.get_or_init(crate::utils::cycles::calibrate_frequency)
 }
 
-/// Get the measurement unit name
-#[cfg(all(feature = "cpu_cycles", not(feature = "use_time")))]
-pub const fn unit_name() -> &'static str {
-    #[cfg(target_arch = "aarch64")]
-    {
-        "ticks"
-    }
-    #[cfg(target_arch = "x86_64")]
-    {
-        "cycles"
-    }
-    #[cfg(not(any(target_arch = "aarch64", target_arch = "x86_64")))]
-    {
-        "units"
+#[cfg(not(any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64")))]
+pub fn counter_frequency() -> Option<f64> {
+    None
+}
+
+/// Nanoseconds per measurement unit (1 for `monotonic`; `None` for core
+/// cycles, whose rate varies, or if the counter could not be calibrated)
+pub fn nanos_per_unit() -> Option<f64> {
+    let clock = clock::selected();
+    if clock.is_wall_clock() {
+        Some(1.0)
+    } else if clock.reads_tsc() {
+        counter_frequency().map(|hz| 1e9 / hz)
+    } else {
+        None
     }
 }
 
-#[cfg(any(not(feature = "cpu_cycles"), feature = "use_time"))]
-pub const fn unit_name() -> &'static str {
-    "ns"
+/// Format nanoseconds with a unit suited to the magnitude ("12.34ns", "1.234µs", ...)
+pub fn format_nanos(nanos: f64) -> String {
+    if nanos >= 1_000_000_000.0 {
+        format!("{:.3}s", nanos / 1_000_000_000.0)
+    } else if nanos >= 1_000_000.0 {
+        format!("{:.3}ms", nanos / 1_000_000.0)
+    } else if nanos >= 1_000.0 {
+        format!("{:.3}µs", nanos / 1_000.0)
+    } else {
+        format!("{:.2}ns", nanos)
+    }
 }
 
-/// Format a Duration for display with appropriate unit based on features
+/// Format a Duration for display with the unit of the selected clock
 pub fn format_measurement(d: std::time::Duration) -> String {
-    #[cfg(all(feature = "cpu_cycles", not(feature = "use_time")))]
-    {
+    if measures_cycles() {
         format!("{} {}", d.as_nanos(), unit_name())
-    }
-
-    #[cfg(any(not(feature = "cpu_cycles"), feature = "use_time"))]
-    {
+    } else {
         format!("{:?}", d)
     }
 }
 
 /// Format a Duration with floating-point precision (for averages)
 pub fn format_measurement_precise(nanos_f64: f64) -> String {
-    #[cfg(all(feature = "cpu_cycles", not(feature = "use_time")))]
-    {
-        format!("{:.2} {}", nanos_f64, unit_name())
-    }
-
-    #[cfg(any(not(feature = "cpu_cycles"), feature = "use_time"))]
-    {
-        if nanos_f64 >= 1_000_000_000.0 {
-            format!("{:.3}s", nanos_f64 / 1_000_000_000.0)
-        } else if nanos_f64 >= 1_000_000.0 {
-            format!("{:.3}ms", nanos_f64 / 1_000_000.0)
-        } else if nanos_f64 >= 1_000.0 {
-            format!("{:.3}µs", nanos_f64 / 1_000.0)
-        } else {
-            format!("{:.2}ns", nanos_f64)
-        }
+    format_in_unit(nanos_f64, unit_name())
+}
+
+/// Format an average measured in `unit` ("ns", "cycles", ...), e.g. from a
+/// loaded report rather than the selected clock
+pub fn format_in_unit(value: f64, unit: &str) -> String {
+    if unit == clock::ClockKind::Monotonic.unit() {
+        format_nanos(value)
+    } else {
+        format!("{:.2} {}", value, unit)
     }
 }
 
 /// Measures a single expression, returning (measurement, result).
 /// Use inside variant closures to eliminate Fn trait overhead from timing.
 ///
+/// When hardware counters are enabled on the current thread (see
+/// `utils::perf::enable`), they are read just outside the timed region and
+/// the deltas can be retrieved with `utils::perf::take_last`.
+///
 /// # Example
 /// ```ignore
 /// use micro_optimize_algo::measure;
@@ -120,13 +112,123 @@ pub fn format_measurement_precise(nanos_f64: f64) -> String {
 #[macro_export]
 macro_rules! measure {
     ($expr:expr) => {{
+        let counters = $crate::utils::perf::begin();
         let start = $crate::utils::bench::now();
         let result = ::std::hint::black_box($expr);
         let elapsed = $crate::utils::bench::elapsed(start);
+        $crate::utils::perf::end(counters);
+        (elapsed, result)
+    }};
+}
+
+/// Runs an expression `batch_size()` times in one timed region, returning
+/// (measurement for the whole batch, result of the last iteration).
+///
+/// For operations too short to time with a single pair of counter reads. The
+/// runner chooses the batch size and divides the statistics by it, so results
+/// are reported per iteration. The loop itself (one compare and branch per
+/// iteration) is part of the measured cost.
+///
+/// # Example
+/// ```ignore
+/// use micro_optimize_algo::measure_batched;
+/// let (elapsed, result) = measure_batched!(next(&mut state));
+/// ```
+#[macro_export]
+macro_rules! measure_batched {
+    ($expr:expr) => {{
+        let iterations = $crate::utils::bench::begin_batch();
+        let counters = $crate::utils::perf::begin();
+        let start = $crate::utils::bench::now();
+        let mut result = ::std::hint::black_box($expr);
+        for _ in 1..iterations {
+            result = ::std::hint::black_box($expr);
+        }
+        let elapsed = $crate::utils::bench::elapsed(start);
+        $crate::utils::perf::end(counters);
         (elapsed, result)
     }};
 }
 
+thread_local! {
+    static BATCH_SIZE: Cell<usize> = const { Cell::new(1) };
+    static BATCH_USED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Set the number of iterations `measure_batched!` runs per timed region on this thread
+pub fn set_batch_size(iterations: usize) {
+    BATCH_SIZE.set(iterations.max(1));
+}
+
+/// Batch size for the next `measure_batched!` (used by the macro)
+#[doc(hidden)]
+pub fn begin_batch() -> usize {
+    BATCH_USED.set(true);
+    BATCH_SIZE.get()
+}
+
+/// Whether `measure_batched!` ran since the last call (clears the flag)
+pub fn take_batch_used() -> bool {
+    BATCH_USED.replace(false)
+}
+
+/// Subtract a constant overhead (cycles or nanoseconds) from a measurement, saturating at zero
+pub fn subtract_overhead(m: Measurement, overhead: u64) -> Measurement {
+    m.saturating_sub(overhead)
+}
+
+/// Number of empty measurements taken by `calibrate_overhead`
+pub const CALIBRATION_SAMPLES: usize = 10_000;
+
+/// Cost of an empty `measure!` on the active measurement backend
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimerOverhead {
+    /// Median of the empty measurements (cycles or nanoseconds)
+    pub median: u64,
+    /// Spread of the empty measurements (90th minus 10th percentile): differences
+    /// smaller than this cannot be told apart from timer jitter
+    pub noise_floor: u64,
+    /// Number of empty measurements taken
+    pub samples: usize,
+}
+
+impl TimerOverhead {
+    /// Whether a mean is indistinguishable from an empty measurement.
+    ///
+    /// `subtracted` tells whether the overhead was already removed from `mean`.
+    pub fn is_below_noise_floor(&self, mean: f64, subtracted: bool) -> bool {
+        let cost = if subtracted { mean } else { mean - self.median as f64 };
+        cost <= self.noise_floor as f64
+    }
+}
+
+/// Measure the cost of an empty `measure!` on `core` (the current core if `None`).
+///
+/// Includes everything the macro does around the expression (timer reads,
+/// fences, counter bookkeeping), so the result matches what every sample carries.
+pub fn calibrate_overhead(samples: usize, core: Option<usize>) -> TimerOverhead {
+    let _pin = super::cpu_affinity::CpuPinGuard::on(core);
+
+    for _ in 0..samples / 10 {
+        let _ = crate::measure!(());
+    }
+
+    let mut values: Vec<u64> = (0..samples.max(1))
+        .map(|_| {
+            let (elapsed, _) = crate::measure!(());
+            elapsed
+        })
+        .collect();
+    values.sort_unstable();
+
+    let percentile = |p: usize| values[(values.len() - 1) * p / 100];
+    TimerOverhead {
+        median: percentile(50),
+        noise_floor: percentile(90) - percentile(10),
+        samples: values.len(),
+    }
+}
+
 /// Calculate standard deviation from a list of durations
 pub fn calculate_std_dev(times: &[Duration], mean: Duration) -> Duration {
     if times.len() < 2 {
@@ -169,6 +271,22 @@ pub fn time_seed() -> u64 {
         .unwrap_or(0x12345678)
 }
 
+/// Derive the seed of one input set (algorithm and size) from the global seed.
+///
+/// Every algorithm and size gets independent inputs, all reproducible from the
+/// single `--seed` value.
+pub fn derive_seed(seed: u64, name: &str, size: usize) -> u64 {
+    // FNV-1a over the name and size, then the SplitMix64 finalizer
+    let mut hash = 0xcbf29ce484222325u64 ^ seed;
+    for byte in name.bytes().chain(size.to_le_bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
+}
+
 /// Compute timing statistics from a list of durations
 pub fn compute_stats(times: &[Duration]) -> (Duration, Duration, Duration, Duration) {
     if times.is_empty() {
@@ -216,3 +334,20 @@ impl SeededRng {
         (self.next_u64() >> 32) as u32 % max
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive_seed() {
+        let seed = derive_seed(42, "dot_product", 1024);
+
+        // Deterministic for the same inputs
+        assert_eq!(seed, derive_seed(42, "dot_product", 1024));
+        // Independent per global seed, algorithm and size
+        assert_ne!(seed, derive_seed(43, "dot_product", 1024));
+        assert_ne!(seed, derive_seed(42, "xoroshiro", 1024));
+        assert_ne!(seed, derive_seed(42, "dot_product", 2048));
+    }
+}
@@ -0,0 +1,287 @@
+//! Cache state before each sample (`--cache`).
+//!
+//! Warmup leaves code and data in the caches, so by default every sample
+//! measures the hot case. The cold modes reset the caches before each sample:
+//! input buffers declared by the closure (`VariantClosure::inputs`) are
+//! flushed (`clflush` on x86_64, `dc civac` on aarch64, a read through a
+//! buffer larger than the last-level cache elsewhere), and the instruction
+//! cache is evicted by executing a large block of `nop`s.
+
+use std::hint::black_box;
+
+/// Cache state of each sample
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Code and data cached by the previous runs (warm caches)
+    #[default]
+    Hot,
+    /// Input buffers flushed from every cache level before each sample
+    ColdData,
+    /// Instruction cache evicted before each sample
+    ColdCode,
+    /// Both `ColdData` and `ColdCode`
+    Cold,
+}
+
+impl CacheMode {
+    /// Name used on the command line and in exports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheMode::Hot => "hot",
+            CacheMode::ColdData => "cold-data",
+            CacheMode::ColdCode => "cold-code",
+            CacheMode::Cold => "cold",
+        }
+    }
+
+    /// Parse "hot", "cold-data", "cold-code" or "cold"
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "hot" => Some(CacheMode::Hot),
+            "cold-data" => Some(CacheMode::ColdData),
+            "cold-code" => Some(CacheMode::ColdCode),
+            "cold" => Some(CacheMode::Cold),
+            _ => None,
+        }
+    }
+
+    pub fn is_hot(&self) -> bool {
+        *self == CacheMode::Hot
+    }
+
+    pub fn flushes_data(&self) -> bool {
+        matches!(self, CacheMode::ColdData | CacheMode::Cold)
+    }
+
+    pub fn evicts_code(&self) -> bool {
+        matches!(self, CacheMode::ColdCode | CacheMode::Cold)
+    }
+}
+
+/// Memory read by a variant (an address range, kept alive by the closure that declares it)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputRegion {
+    start: usize,
+    len: usize,
+}
+
+impl InputRegion {
+    /// The memory of `slice`
+    pub fn of<T>(slice: &[T]) -> Self {
+        Self { start: slice.as_ptr() as usize, len: std::mem::size_of_val(slice) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Cache line size assumed when flushing
+const LINE_SIZE: usize = 64;
+
+/// Size of the eviction buffer when the last-level cache size is unknown
+const DEFAULT_EVICTION_BYTES: usize = 64 << 20;
+
+/// Puts the caches in the state of a `CacheMode` before each sample
+pub struct CachePreparer {
+    mode: CacheMode,
+    /// Read to evict data where cache lines cannot be flushed individually
+    eviction_buffer: Vec<u8>,
+}
+
+impl CachePreparer {
+    pub fn new(mode: CacheMode) -> Self {
+        let eviction_buffer = if mode.flushes_data() && !CAN_FLUSH_LINES {
+            let bytes = last_level_cache_size().map(|s| s * 2).unwrap_or(DEFAULT_EVICTION_BYTES);
+            vec![1u8; bytes]
+        } else {
+            Vec::new()
+        };
+        Self { mode, eviction_buffer }
+    }
+
+    pub fn mode(&self) -> CacheMode {
+        self.mode
+    }
+
+    /// Description of what happens before each sample (None when hot)
+    pub fn describe(&self) -> Option<String> {
+        let mut steps = Vec::new();
+        if self.mode.flushes_data() {
+            steps.push(if CAN_FLUSH_LINES {
+                "input buffers flushed".to_string()
+            } else {
+                format!("{} MiB eviction buffer read", self.eviction_buffer.len() >> 20)
+            });
+        }
+        if self.mode.evicts_code() {
+            steps.push(if CAN_EVICT_CODE {
+                format!("{} KiB of code executed", CODE_EVICTION_BYTES >> 10)
+            } else {
+                "instruction cache eviction unsupported on this architecture".to_string()
+            });
+        }
+        (!steps.is_empty()).then(|| steps.join(", "))
+    }
+
+    /// Prepare the caches for a sample of a closure reading `inputs`
+    pub fn prepare(&self, inputs: &[InputRegion]) {
+        if self.mode.flushes_data() {
+            if CAN_FLUSH_LINES {
+                inputs.iter().for_each(flush_region);
+                flush_fence();
+            } else {
+                self.read_eviction_buffer();
+            }
+        }
+        if self.mode.evicts_code() {
+            evict_instruction_cache();
+        }
+    }
+
+    fn read_eviction_buffer(&self) {
+        let mut sum = 0u8;
+        for line in self.eviction_buffer.chunks(LINE_SIZE) {
+            sum = sum.wrapping_add(line[0]);
+        }
+        black_box(sum);
+    }
+}
+
+/// Size of the largest cache of the CPU (Linux sysfs)
+#[cfg(target_os = "linux")]
+pub fn last_level_cache_size() -> Option<usize> {
+    let entries = std::fs::read_dir("/sys/devices/system/cpu/cpu0/cache").ok()?;
+    entries
+        .filter_map(|entry| std::fs::read_to_string(entry.ok()?.path().join("size")).ok())
+        .filter_map(|size| parse_cache_size(size.trim()))
+        .max()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn last_level_cache_size() -> Option<usize> {
+    None
+}
+
+/// Parse a sysfs cache size ("48K", "2048K", "32M")
+#[cfg(any(target_os = "linux", test))]
+fn parse_cache_size(size: &str) -> Option<usize> {
+    let (digits, multiplier) = match size.as_bytes().last()? {
+        b'K' => (&size[..size.len() - 1], 1 << 10),
+        b'M' => (&size[..size.len() - 1], 1 << 20),
+        _ => (size, 1),
+    };
+    digits.parse::<usize>().ok().map(|n| n * multiplier)
+}
+
+const CAN_FLUSH_LINES: bool = cfg!(any(target_arch = "x86_64", target_arch = "aarch64"));
+
+/// Address of every cache line touched by the region, starting with the line
+/// holding its first byte (regions need not be line-aligned)
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64", test))]
+fn region_lines(region: &InputRegion) -> impl Iterator<Item = usize> {
+    (region.start & !(LINE_SIZE - 1)..region.start + region.len).step_by(LINE_SIZE)
+}
+
+#[cfg(target_arch = "x86_64")]
+fn flush_region(region: &InputRegion) {
+    for address in region_lines(region) {
+        // SAFETY: the region is memory owned by a live closure; clflush does not modify it
+        unsafe { std::arch::x86_64::_mm_clflush(address as *const u8) };
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn flush_fence() {
+    // SAFETY: mfence has no preconditions
+    unsafe { std::arch::x86_64::_mm_mfence() };
+}
+
+#[cfg(target_arch = "aarch64")]
+fn flush_region(region: &InputRegion) {
+    for address in region_lines(region) {
+        // SAFETY: the region is memory owned by a live closure; clean and invalidate
+        // writes back and drops the line without changing its contents
+        unsafe { core::arch::asm!("dc civac, {}", in(reg) address, options(nostack, preserves_flags)) };
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+fn flush_fence() {
+    // SAFETY: a barrier has no preconditions
+    unsafe { core::arch::asm!("dsb ish", options(nostack, preserves_flags)) };
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn flush_region(_region: &InputRegion) {}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn flush_fence() {}
+
+const CAN_EVICT_CODE: bool = cfg!(any(target_arch = "x86_64", target_arch = "aarch64"));
+
+/// Bytes of code executed to evict the instruction cache (larger than L1i)
+const CODE_EVICTION_BYTES: usize = 128 << 10;
+
+/// Execute `CODE_EVICTION_BYTES` of `nop`s, replacing the instruction cache contents
+#[cfg(target_arch = "x86_64")]
+#[inline(never)]
+fn evict_instruction_cache() {
+    // SAFETY: nops only (1 byte each)
+    unsafe { core::arch::asm!(".rept 131072", "nop", ".endr", options(nomem, nostack, preserves_flags)) };
+}
+
+#[cfg(target_arch = "aarch64")]
+#[inline(never)]
+fn evict_instruction_cache() {
+    // SAFETY: nops only (4 bytes each)
+    unsafe { core::arch::asm!(".rept 32768", "nop", ".endr", options(nomem, nostack, preserves_flags)) };
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn evict_instruction_cache() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_mode_parse() {
+        for mode in [CacheMode::Hot, CacheMode::ColdData, CacheMode::ColdCode, CacheMode::Cold] {
+            assert_eq!(CacheMode::parse(mode.as_str()), Some(mode));
+        }
+        assert_eq!(CacheMode::parse("warm"), None);
+        assert!(CacheMode::Cold.flushes_data() && CacheMode::Cold.evicts_code());
+        assert!(!CacheMode::ColdCode.flushes_data());
+    }
+
+    #[test]
+    fn test_parse_cache_size() {
+        assert_eq!(parse_cache_size("48K"), Some(48 << 10));
+        assert_eq!(parse_cache_size("32M"), Some(32 << 20));
+        assert_eq!(parse_cache_size("512"), Some(512));
+        assert_eq!(parse_cache_size("lots"), None);
+    }
+
+    #[test]
+    fn test_region_lines() {
+        // 8 bytes straddling two lines, then a line-aligned region of one line
+        let lines: Vec<usize> = region_lines(&InputRegion { start: 4096 + 60, len: 8 }).collect();
+        assert_eq!(lines, vec![4096, 4096 + 64]);
+        assert_eq!(region_lines(&InputRegion { start: 4096, len: 64 }).count(), 1);
+        assert_eq!(region_lines(&InputRegion { start: 4096 + 4, len: 128 }).count(), 3);
+    }
+
+    #[test]
+    fn test_prepare_keeps_data() {
+        let data: Vec<u64> = (0..1000).collect();
+        let preparer = CachePreparer::new(CacheMode::Cold);
+        preparer.prepare(&[InputRegion::of(&data)]);
+        assert_eq!(data.iter().sum::<u64>(), 999 * 1000 / 2);
+        assert_eq!(InputRegion::of(&data).len(), 8000);
+    }
+}
@@ -0,0 +1,266 @@
+//! Measurement clocks (`--clock`).
+//!
+//! Every sample is the difference of two readings of the selected clock,
+//! taken by `measure!`. The clock is chosen at run time, so one binary can
+//! measure with any backend:
+//!
+//! - `rdtsc`: the timestamp counter behind `lfence` (`CNTVCT_EL0` on aarch64)
+//! - `rdtscp`: the timestamp counter read with `rdtscp`, which waits for the
+//!   measured code to retire (x86 only)
+//! - `monotonic`: the monotonic system clock, in nanoseconds
+//! - `perf`: actual core cycles from a `perf_event_open` counter (Linux with a
+//!   PMU); follows frequency changes, unlike the timestamp counter
+//!
+//! Each backend implements `Clock`; `read` dispatches on the selected one with
+//! a predictable branch, so the cost stays in the calibrated timer overhead.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// A source of monotonically increasing readings
+pub trait Clock {
+    /// Name used on the command line and in exports
+    const NAME: &'static str;
+    /// Unit of the readings ("cycles", "ns", ...)
+    const UNIT: &'static str;
+
+    /// Current reading
+    fn read() -> u64;
+
+    /// Why the clock cannot be used on this machine, if it cannot
+    fn unavailable() -> Option<String> {
+        None
+    }
+}
+
+/// Timestamp counter with `lfence` on both sides (`cycles::read_cycles`)
+pub struct Rdtsc;
+
+/// Timestamp counter read with `rdtscp` followed by `lfence`
+pub struct Rdtscp;
+
+/// Monotonic system clock (`std::time::Instant`)
+pub struct Monotonic;
+
+/// Core cycles counted by `perf_event_open`
+pub struct PerfCycles;
+
+impl Clock for Rdtsc {
+    const NAME: &'static str = "rdtsc";
+    const UNIT: &'static str = if cfg!(target_arch = "aarch64") { "ticks" } else { "cycles" };
+
+    #[inline(always)]
+    fn read() -> u64 {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64"))]
+        {
+            super::cycles::read_cycles()
+        }
+        #[cfg(not(any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64")))]
+        {
+            0
+        }
+    }
+
+    fn unavailable() -> Option<String> {
+        (!cfg!(any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64")))
+            .then(|| "no cycle counter on this architecture".to_string())
+    }
+}
+
+impl Clock for Rdtscp {
+    const NAME: &'static str = "rdtscp";
+    const UNIT: &'static str = "cycles";
+
+    #[inline(always)]
+    fn read() -> u64 {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        {
+            #[cfg(target_arch = "x86")]
+            use core::arch::x86::{__rdtscp, _mm_lfence};
+            #[cfg(target_arch = "x86_64")]
+            use core::arch::x86_64::{__rdtscp, _mm_lfence};
+            let mut aux = 0u32;
+            unsafe {
+                // RDTSCP waits for earlier instructions; LFENCE keeps later ones from starting early
+                let cycles = __rdtscp(&mut aux);
+                _mm_lfence();
+                cycles
+            }
+        }
+        #[cfg(not(any(target_arch = "x86_64", target_arch = "x86")))]
+        {
+            0
+        }
+    }
+
+    fn unavailable() -> Option<String> {
+        (!cfg!(any(target_arch = "x86_64", target_arch = "x86"))).then(|| "rdtscp is an x86 instruction".to_string())
+    }
+}
+
+impl Clock for Monotonic {
+    const NAME: &'static str = "monotonic";
+    const UNIT: &'static str = "ns";
+
+    #[inline(always)]
+    fn read() -> u64 {
+        use std::sync::OnceLock;
+        static EPOCH: OnceLock<std::time::Instant> = OnceLock::new();
+        EPOCH.get_or_init(std::time::Instant::now).elapsed().as_nanos() as u64
+    }
+}
+
+impl Clock for PerfCycles {
+    const NAME: &'static str = "perf";
+    const UNIT: &'static str = "core-cycles";
+
+    #[inline(always)]
+    fn read() -> u64 {
+        super::perf::read_core_cycles()
+    }
+
+    fn unavailable() -> Option<String> {
+        super::perf::open_core_cycles().err()
+    }
+}
+
+/// The clock backends, as selected with `--clock`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockKind {
+    Rdtsc,
+    Rdtscp,
+    Monotonic,
+    Perf,
+}
+
+impl Default for ClockKind {
+    /// The timestamp counter where there is one, the system clock elsewhere
+    fn default() -> Self {
+        if Rdtsc::unavailable().is_none() {
+            ClockKind::Rdtsc
+        } else {
+            ClockKind::Monotonic
+        }
+    }
+}
+
+impl ClockKind {
+    pub const ALL: [ClockKind; 4] = [ClockKind::Rdtsc, ClockKind::Rdtscp, ClockKind::Monotonic, ClockKind::Perf];
+
+    /// Name used on the command line and in exports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClockKind::Rdtsc => Rdtsc::NAME,
+            ClockKind::Rdtscp => Rdtscp::NAME,
+            ClockKind::Monotonic => Monotonic::NAME,
+            ClockKind::Perf => PerfCycles::NAME,
+        }
+    }
+
+    /// Parse "rdtsc", "rdtscp", "monotonic" or "perf"
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|clock| clock.as_str() == name)
+    }
+
+    /// Unit of the measurements ("cycles", "ticks", "ns" or "core-cycles")
+    pub fn unit(&self) -> &'static str {
+        match self {
+            ClockKind::Rdtsc => Rdtsc::UNIT,
+            ClockKind::Rdtscp => Rdtscp::UNIT,
+            ClockKind::Monotonic => Monotonic::UNIT,
+            ClockKind::Perf => PerfCycles::UNIT,
+        }
+    }
+
+    /// Whether the clock reads the fixed-rate timestamp counter (calibrated by
+    /// `bench::counter_frequency`)
+    pub fn reads_tsc(&self) -> bool {
+        matches!(self, ClockKind::Rdtsc | ClockKind::Rdtscp)
+    }
+
+    /// Whether the clock measures nanoseconds rather than counter ticks
+    pub fn is_wall_clock(&self) -> bool {
+        *self == ClockKind::Monotonic
+    }
+
+    /// Why the clock cannot be used on this machine, if it cannot
+    pub fn unavailable(&self) -> Option<String> {
+        match self {
+            ClockKind::Rdtsc => Rdtsc::unavailable(),
+            ClockKind::Rdtscp => Rdtscp::unavailable(),
+            ClockKind::Monotonic => Monotonic::unavailable(),
+            ClockKind::Perf => PerfCycles::unavailable(),
+        }
+    }
+}
+
+/// Selected clock, as a `ClockKind` discriminant (`u8::MAX` until `select` is called)
+static SELECTED: AtomicU8 = AtomicU8::new(u8::MAX);
+
+/// Use `clock` for all further measurements of the process.
+///
+/// Fails (keeping the previous clock) when the clock is unavailable here,
+/// e.g. `perf` without access to a hardware cycle counter.
+pub fn select(clock: ClockKind) -> Result<(), String> {
+    if let Some(reason) = clock.unavailable() {
+        return Err(format!("clock '{}' unavailable: {}", clock.as_str(), reason));
+    }
+    SELECTED.store(clock as u8, Ordering::Relaxed);
+    Ok(())
+}
+
+/// The clock used by `measure!` (`ClockKind::default()` unless `select` was called)
+#[inline(always)]
+pub fn selected() -> ClockKind {
+    match SELECTED.load(Ordering::Relaxed) {
+        0 => ClockKind::Rdtsc,
+        1 => ClockKind::Rdtscp,
+        2 => ClockKind::Monotonic,
+        3 => ClockKind::Perf,
+        _ => ClockKind::default(),
+    }
+}
+
+/// Read the selected clock
+#[inline(always)]
+pub fn read() -> u64 {
+    match selected() {
+        ClockKind::Rdtsc => Rdtsc::read(),
+        ClockKind::Rdtscp => Rdtscp::read(),
+        ClockKind::Monotonic => Monotonic::read(),
+        ClockKind::Perf => PerfCycles::read(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_names() {
+        for clock in ClockKind::ALL {
+            assert_eq!(ClockKind::parse(clock.as_str()), Some(clock));
+        }
+        assert_eq!(ClockKind::parse("tsc"), None);
+        assert!(ClockKind::Monotonic.is_wall_clock() && ClockKind::Monotonic.unit() == "ns");
+        assert!(ClockKind::Rdtscp.reads_tsc() && !ClockKind::Perf.reads_tsc());
+    }
+
+    #[test]
+    fn test_available_clocks_advance() {
+        assert!(ClockKind::default().unavailable().is_none());
+        assert!(ClockKind::Monotonic.unavailable().is_none());
+
+        let readings = |read: fn() -> u64| {
+            let start = read();
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            read().saturating_sub(start)
+        };
+        assert!(readings(Monotonic::read) >= 1_000_000);
+        if Rdtsc::unavailable().is_none() {
+            assert!(readings(Rdtsc::read) > 0);
+        }
+        if Rdtscp::unavailable().is_none() {
+            assert!(readings(Rdtscp::read) > 0);
+        }
+    }
+}
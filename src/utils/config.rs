@@ -0,0 +1,365 @@
+//! Benchmark configuration files (`--config`).
+//!
+//! A configuration file (TOML, or JSON for `.json` files) holds the same
+//! settings as the command line. Top-level keys are the defaults; named
+//! profiles under `[profiles.<name>]` override them (`--profile <name>`):
+//!
+//! ```toml
+//! runs = 100
+//! sizes = [64, 1024, 16384]
+//! json = "results.json"
+//!
+//! [algorithm_sizes]
+//! call_vs_branch = [64]
+//!
+//! [profiles.quick]
+//! runs = 10
+//! sizes = [1024]
+//! ```
+//!
+//! Options given on the command line take precedence over the file.
+
+use std::time::Duration;
+
+use super::cache::CacheMode;
+use super::clock::ClockKind;
+use super::cpu_affinity::parse_cpu_list;
+use super::json::JsonValue;
+use super::runner::BenchmarkOptions;
+use super::tags::Language;
+use super::timer::{Batching, PinStrategy};
+use crate::registry::closest_match;
+
+/// Algorithms to run, by name and by category (all when both are empty)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AlgorithmSelection {
+    pub names: Vec<String>,
+    pub categories: Vec<String>,
+}
+
+impl AlgorithmSelection {
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty() && self.categories.is_empty()
+    }
+}
+
+/// A parsed configuration file
+#[derive(Clone, Debug)]
+pub struct ConfigFile {
+    path: String,
+    root: JsonValue,
+}
+
+impl ConfigFile {
+    /// Load a TOML file (JSON if the extension is `.json`)
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let root = if path.ends_with(".json") {
+            JsonValue::parse(&text)
+        } else {
+            super::toml::parse(&text)
+        }
+        .map_err(|e| format!("{}: {}", path, e))?;
+
+        if !matches!(root, JsonValue::Object(_)) {
+            return Err(format!("{}: expected a table of settings", path));
+        }
+        Ok(Self { path: path.to_string(), root })
+    }
+
+    /// Names of the profiles defined in the file
+    pub fn profiles(&self) -> Vec<&str> {
+        match self.root.get("profiles") {
+            Some(JsonValue::Object(fields)) => fields.iter().map(|(name, _)| name.as_str()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Apply the top-level settings, then those of `profile`
+    pub fn apply(
+        &self,
+        profile: Option<&str>,
+        options: &mut BenchmarkOptions,
+        selection: &mut AlgorithmSelection,
+    ) -> Result<(), String> {
+        apply_settings(&self.root, true, options, selection).map_err(|e| format!("{}: {}", self.path, e))?;
+
+        let Some(name) = profile else {
+            return Ok(());
+        };
+        match self.root.get("profiles").and_then(|p| p.get(name)) {
+            Some(settings) => apply_settings(settings, false, options, selection)
+                .map_err(|e| format!("{}: profile '{}': {}", self.path, name, e)),
+            None => {
+                let profiles = self.profiles();
+                let hint = closest_match(name, &profiles)
+                    .map(|s| format!(" Did you mean '{}'?", s))
+                    .unwrap_or_default();
+                Err(format!(
+                    "{}: profile '{}' not found.{} Available: {}",
+                    self.path,
+                    name,
+                    hint,
+                    if profiles.is_empty() { "none".to_string() } else { profiles.join(", ") }
+                ))
+            }
+        }
+    }
+}
+
+/// Apply one table of settings (`top_level` allows the `profiles` table)
+fn apply_settings(
+    settings: &JsonValue,
+    top_level: bool,
+    options: &mut BenchmarkOptions,
+    selection: &mut AlgorithmSelection,
+) -> Result<(), String> {
+    let JsonValue::Object(fields) = settings else {
+        return Err("expected a table of settings".to_string());
+    };
+
+    for (key, value) in fields {
+        let timing = &mut options.timing;
+        let filter = &mut options.variant_filter;
+        match key.as_str() {
+            "profiles" if top_level => {}
+            "algorithms" => selection.names = strings(key, value)?,
+            "categories" => selection.categories = strings(key, value)?,
+            "variants" => filter.include = strings(key, value)?,
+            "exclude" => filter.exclude = strings(key, value)?,
+            "lang" => {
+                let lang = string(key, value)?;
+                filter.lang = Some(Language::parse(&lang).ok_or_else(|| format!("unknown language '{}'", lang))?);
+            }
+            "arch" => filter.arch = Some(string(key, value)?),
+            "simd" => filter.simd = Some(string(key, value)?),
+            "technique" => filter.technique = Some(string(key, value)?),
+            "sizes" => options.input_sizes = Some(sizes(key, value)?),
+            "algorithm_sizes" => {
+                let JsonValue::Object(entries) = value else {
+                    return Err("'algorithm_sizes' must be a table of algorithm = [sizes]".to_string());
+                };
+                for (algorithm, value) in entries {
+                    let sizes = sizes(algorithm, value)?;
+                    options.algorithm_sizes.retain(|(name, _)| name != algorithm);
+                    options.algorithm_sizes.push((algorithm.clone(), sizes));
+                }
+            }
+            "params" => {
+                let JsonValue::Object(entries) = value else {
+                    return Err("'params' must be a table of parameter = [values]".to_string());
+                };
+                for (name, value) in entries {
+                    let values = strings(name, value)?;
+                    options.param_values.retain(|(n, _)| n != name);
+                    options.param_values.push((name.clone(), values));
+                }
+            }
+            "runs" => timing.runs_per_variant = positive(key, value)?,
+            "warmup" => timing.warmup_iterations = integer(key, value)? as usize,
+            "max_runs" => timing.max_runs_per_variant = positive(key, value)?,
+            "precision" => timing.target_precision = Some(positive_float(key, value)? / 100.0),
+            "time_budget" => timing.time_budget = Some(Duration::from_secs_f64(positive_float(key, value)?)),
+            "pin" => {
+                let pin = string(key, value)?;
+                timing.pin_strategy =
+                    PinStrategy::parse(&pin).ok_or_else(|| format!("unknown pin strategy '{}'", pin))?;
+            }
+            "batch" => {
+                timing.batching = match value {
+                    JsonValue::String(mode) => {
+                        Batching::parse(mode).ok_or_else(|| format!("unknown batch mode '{}'", mode))?
+                    }
+                    _ => Batching::Fixed(positive(key, value)?),
+                }
+            }
+            "cache" => {
+                let cache = string(key, value)?;
+                timing.cache = CacheMode::parse(&cache).ok_or_else(|| format!("unknown cache mode '{}'", cache))?;
+            }
+            "clock" => {
+                let clock = string(key, value)?;
+                timing.clock = ClockKind::parse(&clock).ok_or_else(|| format!("unknown clock '{}'", clock))?;
+            }
+            "cores" => timing.cores = cores(key, value)?,
+            "core_classes" => options.core_classes = boolean(key, value)?,
+            "subtract_overhead" => timing.subtract_overhead = boolean(key, value)?,
+            "perf" => timing.hw_counters = boolean(key, value)?,
+            "seed" => options.seed = Some(integer(key, value)?),
+            "filter_outliers" => options.filter_outliers = boolean(key, value)?,
+            "strict" => options.strict = boolean(key, value)?,
+            "csv" => options.csv_path = Some(string(key, value)?),
+            "raw_csv" => options.raw_csv_path = Some(string(key, value)?),
+            "json" => options.json_path = Some(string(key, value)?),
+            "save_baseline" => options.save_path = Some(string(key, value)?),
+            "compare" => options.compare_path = Some(string(key, value)?),
+            _ => {
+                let hint = closest_match(key, SETTINGS)
+                    .map(|s| format!(" Did you mean '{}'?", s))
+                    .unwrap_or_default();
+                return Err(format!("unknown setting '{}'.{}", key, hint));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Keys accepted by `apply_settings` (for suggestions)
+const SETTINGS: &[&str] = &[
+    "algorithms", "categories", "variants", "exclude", "lang", "arch", "simd", "technique", "sizes",
+    "algorithm_sizes", "params", "runs", "warmup", "max_runs", "precision", "time_budget", "pin", "batch",
+    "cache", "clock", "cores", "core_classes", "subtract_overhead", "perf", "seed", "filter_outliers", "strict", "csv",
+    "raw_csv", "json", "save_baseline", "compare", "profiles",
+];
+
+fn string(key: &str, value: &JsonValue) -> Result<String, String> {
+    value
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| format!("'{}' must be a string", key))
+}
+
+fn strings(key: &str, value: &JsonValue) -> Result<Vec<String>, String> {
+    match value {
+        JsonValue::String(s) => Ok(vec![s.clone()]),
+        JsonValue::Array(items) => items.iter().map(|item| string(key, item)).collect(),
+        _ => Err(format!("'{}' must be a string or a list of strings", key)),
+    }
+}
+
+fn integer(key: &str, value: &JsonValue) -> Result<u64, String> {
+    value
+        .as_u64()
+        .ok_or_else(|| format!("'{}' must be a non-negative integer", key))
+}
+
+fn positive(key: &str, value: &JsonValue) -> Result<usize, String> {
+    match value.as_u64() {
+        Some(n) if n > 0 => Ok(n as usize),
+        _ => Err(format!("'{}' must be a positive integer", key)),
+    }
+}
+
+fn positive_float(key: &str, value: &JsonValue) -> Result<f64, String> {
+    match value.as_f64() {
+        Some(x) if x > 0.0 => Ok(x),
+        _ => Err(format!("'{}' must be a positive number", key)),
+    }
+}
+
+fn boolean(key: &str, value: &JsonValue) -> Result<bool, String> {
+    value.as_bool().ok_or_else(|| format!("'{}' must be true or false", key))
+}
+
+/// A core number, a list of core numbers or a CPU list string ("4-7")
+fn cores(key: &str, value: &JsonValue) -> Result<Vec<usize>, String> {
+    let cores = match value {
+        JsonValue::String(list) => parse_cpu_list(list),
+        JsonValue::Array(items) => items.iter().map(|item| integer(key, item).map(|c| c as usize)).collect::<Result<_, _>>()?,
+        _ => vec![integer(key, value)? as usize],
+    };
+    if cores.is_empty() {
+        return Err(format!("'{}' must list at least one core", key));
+    }
+    Ok(cores)
+}
+
+fn sizes(key: &str, value: &JsonValue) -> Result<Vec<usize>, String> {
+    let items = value
+        .as_array()
+        .filter(|items| !items.is_empty())
+        .ok_or_else(|| format!("'{}' must be a non-empty list of sizes", key))?;
+    items.iter().map(|item| positive(key, item)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::SIZE_INDEPENDENT;
+
+    fn config(text: &str) -> ConfigFile {
+        ConfigFile { path: "bench.toml".to_string(), root: super::super::toml::parse(text).unwrap() }
+    }
+
+    const EXAMPLE: &str = r#"
+runs = 100
+warmup = 50
+sizes = [64, 1024]
+seed = 7
+algorithms = ["dot_product"]
+json = "results.json"
+
+[algorithm_sizes]
+call_vs_branch = [64]
+
+[profiles.quick]
+runs = 10
+pin = "global"
+cores = "2-3"
+core_classes = true
+categories = "control_flow"
+variants = ["*/original"]
+
+[profiles.quick.algorithm_sizes]
+dot_product = [256]
+
+[profiles.quick.params]
+pattern = "sorted"
+"#;
+
+    #[test]
+    fn test_defaults_and_profile() {
+        let registry = crate::registry::build_registry();
+        let dot_product = registry.find("dot_product").unwrap();
+        let call_vs_branch = registry.find("call_vs_branch").unwrap();
+        let xoroshiro = registry.find("xoroshiro128++").unwrap();
+        let file = config(EXAMPLE);
+        assert_eq!(file.profiles(), vec!["quick"]);
+
+        let mut options = BenchmarkOptions::default();
+        let mut selection = AlgorithmSelection::default();
+        file.apply(None, &mut options, &mut selection).unwrap();
+        assert_eq!(options.timing.runs_per_variant, 100);
+        assert_eq!(options.timing.warmup_iterations, 50);
+        assert_eq!(options.input_sizes, Some(vec![64, 1024]));
+        assert_eq!(options.seed, Some(7));
+        assert_eq!(options.json_path.as_deref(), Some("results.json"));
+        assert_eq!(options.sizes_for(call_vs_branch), vec![64]);
+        assert_eq!(options.sizes_for(dot_product), vec![64, 1024]);
+        // Size-independent algorithms run once whatever the sizes
+        assert_eq!(options.sizes_for(xoroshiro), vec![SIZE_INDEPENDENT]);
+        assert_eq!(selection.names, vec!["dot_product"]);
+
+        let mut options = BenchmarkOptions::default();
+        let mut selection = AlgorithmSelection::default();
+        file.apply(Some("quick"), &mut options, &mut selection).unwrap();
+        assert_eq!(options.timing.runs_per_variant, 10);
+        assert_eq!(options.timing.warmup_iterations, 50);
+        assert_eq!(options.timing.pin_strategy, PinStrategy::Global);
+        assert_eq!(options.timing.cores, vec![2, 3]);
+        assert!(options.core_classes);
+        assert_eq!(options.variant_filter.include, vec!["*/original"]);
+        assert_eq!(options.param_values, vec![("pattern".to_string(), vec!["sorted".to_string()])]);
+        assert_eq!(options.sizes_for(dot_product), vec![256]);
+        assert_eq!(options.sizes_for(call_vs_branch), vec![64]);
+        assert_eq!(selection.categories, vec!["control_flow"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut options = BenchmarkOptions::default();
+        let mut selection = AlgorithmSelection::default();
+
+        let err = config(EXAMPLE).apply(Some("quik"), &mut options, &mut selection).unwrap_err();
+        assert!(err.contains("Did you mean 'quick'?"), "{}", err);
+
+        let err = config("rusn = 10").apply(None, &mut options, &mut selection).unwrap_err();
+        assert!(err.contains("unknown setting 'rusn'. Did you mean 'runs'?"), "{}", err);
+
+        assert!(config("runs = 0").apply(None, &mut options, &mut selection).is_err());
+        assert!(config("sizes = []").apply(None, &mut options, &mut selection).is_err());
+        assert!(config("pin = \"sometimes\"").apply(None, &mut options, &mut selection).is_err());
+        assert!(config("cores = \"\"").apply(None, &mut options, &mut selection).is_err());
+        assert!(config("[profiles.a]\nprofiles = 1").apply(Some("a"), &mut options, &mut selection).is_err());
+    }
+}
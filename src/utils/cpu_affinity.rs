@@ -270,6 +270,210 @@ pub fn get_current_cpu() -> Option<usize> {
     platform::get_current_cpu()
 }
 
+/// Parse a Linux CPU list ("0-3,8,10-11", as in sysfs and `isolcpus=`).
+/// Invalid entries are ignored.
+pub fn parse_cpu_list(list: &str) -> Vec<usize> {
+    let mut cpus = Vec::new();
+    for range in list.trim().split(',').map(str::trim).filter(|r| !r.is_empty()) {
+        match range.split_once('-') {
+            Some((first, last)) => {
+                if let (Ok(first), Ok(last)) = (first.parse::<usize>(), last.parse::<usize>()) {
+                    cpus.extend(first..=last);
+                }
+            }
+            None => cpus.extend(range.parse::<usize>().ok()),
+        }
+    }
+    cpus
+}
+
+/// Cores reserved with `isolcpus=` (`/sys/devices/system/cpu/isolated`)
+#[cfg(target_os = "linux")]
+pub fn isolated_cores() -> Vec<usize> {
+    std::fs::read_to_string("/sys/devices/system/cpu/isolated")
+        .map(|list| parse_cpu_list(&list))
+        .unwrap_or_default()
+}
+
+/// Cores reserved with `isolcpus=` (Linux only)
+#[cfg(not(target_os = "linux"))]
+pub fn isolated_cores() -> Vec<usize> {
+    Vec::new()
+}
+
+/// Cores to benchmark on: `requested` if any, otherwise the first isolated core
+/// the thread can be pinned to (isolated cores outside the cpuset of the
+/// process are skipped).
+///
+/// Empty when neither is available, in which case the thread is pinned to
+/// whichever core it is running on.
+pub fn benchmark_cores(requested: &[usize]) -> Vec<usize> {
+    if !requested.is_empty() {
+        return requested.to_vec();
+    }
+    isolated_cores().into_iter().find(|&core| can_pin(core)).into_iter().collect()
+}
+
+/// Check that every core of `cores` exists and that the thread can actually be
+/// pinned to it.
+///
+/// Pinning fails for cores outside the cpuset of the process (e.g. in a
+/// container) and on platforms without real pinning (macOS); failing here
+/// keeps the benchmark from running unpinned. Cores reserved with `isolcpus=`
+/// are not in the inherited affinity mask but can be pinned to.
+pub fn check_cores(cores: &[usize]) -> Result<(), String> {
+    if let Some(available) = get_core_ids() {
+        if let Some(core) = cores.iter().find(|c| !available.contains(c)) {
+            return Err(format!(
+                "core {} does not exist ({} cores online: 0-{})",
+                core,
+                available.len(),
+                available.len().saturating_sub(1)
+            ));
+        }
+    }
+    match cores.iter().find(|&&core| !can_pin(core)) {
+        Some(core) => Err(format!(
+            "cannot pin the thread to core {} (outside the cpuset of the process, or no pinning on this platform)",
+            core
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Whether the thread can be pinned to `core` (it is unpinned again right away)
+fn can_pin(core: usize) -> bool {
+    CpuPinGuard::with_core(core).is_pinned()
+}
+
+// ============================================================================
+// Core classes (hybrid CPUs)
+// ============================================================================
+
+/// What sysfs reports about the type of a core (Linux only)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CoreInfo {
+    pub core: usize,
+    /// `topology/core_type` (e.g. "intel_core", "intel_atom"), where the kernel exposes it
+    pub core_type: Option<String>,
+    /// `cpu_capacity`: relative performance, 1024 for the fastest cores (arm64, hybrid x86)
+    pub capacity: Option<u64>,
+    /// `cpufreq/cpuinfo_max_freq`, in kHz
+    pub max_freq_khz: Option<u64>,
+}
+
+/// A group of cores of the same type, e.g. the P-cores of a hybrid CPU
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoreClass {
+    /// "P-cores" and "E-cores" with two classes, "class 1", "class 2", ... with more
+    pub name: String,
+    /// What the cores of the class have in common (e.g. "cpu_capacity 1024")
+    pub detail: String,
+    pub cores: Vec<usize>,
+}
+
+/// Group cores by type, fastest class first.
+///
+/// Cores are told apart by `core_type` if every core reports one, otherwise by
+/// `cpu_capacity`, otherwise by maximum frequency. Empty when no source covers
+/// every core.
+pub fn classify_cores(infos: &[CoreInfo]) -> Vec<CoreClass> {
+    type Key = fn(&CoreInfo) -> Option<String>;
+    let sources: [Key; 3] = [
+        |info| info.core_type.as_ref().map(|t| format!("core_type {}", t)),
+        |info| info.capacity.map(|c| format!("cpu_capacity {}", c)),
+        |info| info.max_freq_khz.map(|f| format!("max {:.2} GHz", f as f64 / 1e6)),
+    ];
+    let Some(key) = sources.into_iter().find(|key| !infos.is_empty() && infos.iter().all(|i| key(i).is_some())) else {
+        return Vec::new();
+    };
+
+    // (detail, rank, cores) in order of first core
+    let mut groups: Vec<(String, (u64, u64), Vec<usize>)> = Vec::new();
+    for info in infos {
+        let detail = key(info).unwrap_or_default();
+        let rank = (info.capacity.unwrap_or(0), info.max_freq_khz.unwrap_or(0));
+        match groups.iter_mut().find(|(d, _, _)| *d == detail) {
+            Some((_, best, cores)) => {
+                *best = (*best).max(rank);
+                cores.push(info.core);
+            }
+            None => groups.push((detail, rank, vec![info.core])),
+        }
+    }
+    // Stable: classes that cannot be ranked keep the order of their first core
+    groups.sort_by_key(|g| std::cmp::Reverse(g.1));
+
+    let count = groups.len();
+    groups
+        .into_iter()
+        .enumerate()
+        .map(|(i, (detail, _, cores))| {
+            let name = match (count, i) {
+                (2, 0) => "P-cores".to_string(),
+                (2, _) => "E-cores".to_string(),
+                (_, i) => format!("class {}", i + 1),
+            };
+            CoreClass { name, detail, cores }
+        })
+        .collect()
+}
+
+/// Read the type of `core` from `/sys/devices/system/cpu/cpuN`
+#[cfg(target_os = "linux")]
+pub fn core_info(core: usize) -> CoreInfo {
+    let read = |file: &str| {
+        std::fs::read_to_string(format!("/sys/devices/system/cpu/cpu{}/{}", core, file))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    CoreInfo {
+        core,
+        core_type: read("topology/core_type"),
+        capacity: read("cpu_capacity").and_then(|s| s.parse().ok()),
+        max_freq_khz: read("cpufreq/cpuinfo_max_freq").and_then(|s| s.parse().ok()),
+    }
+}
+
+/// Read the type of `core` (Linux only)
+#[cfg(not(target_os = "linux"))]
+pub fn core_info(core: usize) -> CoreInfo {
+    CoreInfo { core, ..CoreInfo::default() }
+}
+
+/// Core classes of this machine, restricted to the `requested` cores if any.
+///
+/// A single class means every core is of the same type (or types could not be
+/// detected, in which case the list is empty).
+pub fn core_classes(requested: &[usize]) -> Vec<CoreClass> {
+    let infos: Vec<CoreInfo> = get_core_ids().unwrap_or_default().into_iter().map(core_info).collect();
+    let mut classes = classify_cores(&infos);
+    if !requested.is_empty() {
+        for class in &mut classes {
+            class.cores.retain(|c| requested.contains(c));
+        }
+        classes.retain(|class| !class.cores.is_empty());
+    }
+    classes
+}
+
+/// Format cores as a CPU list ("0-3,8"), the inverse of `parse_cpu_list`
+pub fn format_cpu_list(cores: &[usize]) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &core in cores {
+        match ranges.last_mut() {
+            Some((_, last)) if *last + 1 == core => *last = core,
+            _ => ranges.push((core, core)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|&(first, last)| if first == last { first.to_string() } else { format!("{}-{}", first, last) })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
 /// Pin the current thread to a specific core.
 ///
 /// Saves the current affinity before pinning so it can be restored later.
@@ -359,6 +563,11 @@ impl CpuPinGuard {
         }
     }
 
+    /// Create a new guard that pins to `core`, or to the current core if `None`.
+    pub fn on(core: Option<usize>) -> Self {
+        core.map_or_else(Self::new, Self::with_core)
+    }
+
     /// Create a new guard that pins to the first available core.
     pub fn first_core() -> Self {
         Self {
@@ -402,6 +611,59 @@ mod tests {
         assert!(!cores.unwrap().is_empty(), "Should have at least one core");
     }
 
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list("5"), vec![5]);
+        assert_eq!(parse_cpu_list(""), Vec::<usize>::new());
+        assert_eq!(parse_cpu_list("2,x,4"), vec![2, 4]);
+    }
+
+    #[test]
+    fn test_benchmark_cores() {
+        assert_eq!(benchmark_cores(&[3, 1]), vec![3, 1]);
+        let automatic = benchmark_cores(&[]);
+        assert!(automatic.len() <= 1 && automatic.iter().all(|c| isolated_cores().contains(c)));
+
+        if let Some(current) = get_current_cpu() {
+            assert!(check_cores(&[current]).is_ok());
+        }
+        if get_core_ids().is_some() {
+            assert!(check_cores(&[0, usize::MAX]).unwrap_err().contains("does not exist"));
+        }
+    }
+
+    #[test]
+    fn test_classify_cores() {
+        let info = |core, capacity, max_freq_khz| CoreInfo {
+            core,
+            capacity: Some(capacity),
+            max_freq_khz: Some(max_freq_khz),
+            ..CoreInfo::default()
+        };
+        let hybrid = [info(0, 1024, 5_400_000), info(1, 1024, 5_400_000), info(2, 512, 4_200_000), info(3, 512, 4_200_000)];
+        let classes = classify_cores(&hybrid);
+        assert_eq!(classes.len(), 2);
+        assert_eq!((classes[0].name.as_str(), classes[0].cores.as_slice()), ("P-cores", &[0, 1][..]));
+        assert_eq!((classes[1].name.as_str(), classes[1].cores.as_slice()), ("E-cores", &[2, 3][..]));
+        assert_eq!(classes[1].detail, "cpu_capacity 512");
+
+        // Ranked by performance, not by core number
+        let big_little = [info(0, 446, 1_800_000), info(1, 1024, 2_800_000), info(2, 870, 2_400_000)];
+        let names: Vec<(String, Vec<usize>)> = classify_cores(&big_little).into_iter().map(|c| (c.name, c.cores)).collect();
+        assert_eq!(names, vec![("class 1".into(), vec![1]), ("class 2".into(), vec![2]), ("class 3".into(), vec![0])]);
+
+        // Frequency only when capacity is missing on some core
+        let freq_only = [CoreInfo { capacity: None, ..info(0, 0, 3_000_000) }, info(1, 1024, 3_000_000)];
+        let classes = classify_cores(&freq_only);
+        assert_eq!(classes.len(), 1);
+        assert_eq!(classes[0].detail, "max 3.00 GHz");
+
+        assert!(classify_cores(&[CoreInfo::default()]).is_empty());
+        assert_eq!(format_cpu_list(&[0, 1, 2, 3, 8, 10, 11]), "0-3,8,10-11");
+        assert_eq!(parse_cpu_list(&format_cpu_list(&[5])), vec![5]);
+    }
+
     #[test]
     fn test_pin_guard() {
         let guard = CpuPinGuard::new();
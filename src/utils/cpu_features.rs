@@ -0,0 +1,101 @@
+//! Runtime CPU feature detection for SIMD variants.
+//!
+//! Variants compiled with `#[target_feature(enable = ...)]` declare the features
+//! they need in `VariantInfo::required_features`; they are only registered when
+//! the running CPU reports all of them.
+
+/// Check whether the running CPU supports a feature (names as in `#[target_feature]`).
+///
+/// Unknown features and features of other architectures are reported as unsupported.
+#[cfg(target_arch = "x86_64")]
+pub fn is_supported(feature: &str) -> bool {
+    match feature {
+        "sse2" => is_x86_feature_detected!("sse2"),
+        "sse3" => is_x86_feature_detected!("sse3"),
+        "ssse3" => is_x86_feature_detected!("ssse3"),
+        "sse4.1" => is_x86_feature_detected!("sse4.1"),
+        "sse4.2" => is_x86_feature_detected!("sse4.2"),
+        "popcnt" => is_x86_feature_detected!("popcnt"),
+        "avx" => is_x86_feature_detected!("avx"),
+        "avx2" => is_x86_feature_detected!("avx2"),
+        "fma" => is_x86_feature_detected!("fma"),
+        "bmi1" => is_x86_feature_detected!("bmi1"),
+        "bmi2" => is_x86_feature_detected!("bmi2"),
+        "lzcnt" => is_x86_feature_detected!("lzcnt"),
+        "avx512f" => is_x86_feature_detected!("avx512f"),
+        "avx512bw" => is_x86_feature_detected!("avx512bw"),
+        "avx512vl" => is_x86_feature_detected!("avx512vl"),
+        _ => false,
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+pub fn is_supported(feature: &str) -> bool {
+    match feature {
+        "neon" => std::arch::is_aarch64_feature_detected!("neon"),
+        "sve" => std::arch::is_aarch64_feature_detected!("sve"),
+        "sve2" => std::arch::is_aarch64_feature_detected!("sve2"),
+        "dotprod" => std::arch::is_aarch64_feature_detected!("dotprod"),
+        _ => false,
+    }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub fn is_supported(_feature: &str) -> bool {
+    false
+}
+
+/// Features `is_supported` knows about on this architecture
+#[cfg(target_arch = "x86_64")]
+pub const KNOWN_FEATURES: &[&str] = &[
+    "sse2", "sse3", "ssse3", "sse4.1", "sse4.2", "popcnt", "avx", "avx2", "fma", "bmi1", "bmi2", "lzcnt",
+    "avx512f", "avx512bw", "avx512vl",
+];
+
+#[cfg(target_arch = "aarch64")]
+pub const KNOWN_FEATURES: &[&str] = &["neon", "sve", "sve2", "dotprod"];
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub const KNOWN_FEATURES: &[&str] = &[];
+
+/// Features from `required` that the running CPU does not support
+pub fn missing_features(required: &[&'static str]) -> Vec<&'static str> {
+    required.iter().copied().filter(|f| !is_supported(f)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_feature_unsupported() {
+        assert!(!is_supported("not-a-feature"));
+        assert_eq!(missing_features(&["not-a-feature"]), vec!["not-a-feature"]);
+        assert!(missing_features(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_split_supported() {
+        use crate::utils::{split_supported, VariantInfo};
+
+        let variants = vec![
+            VariantInfo { name: "portable", description: "", function: (), required_features: &[], tags: Default::default() },
+            VariantInfo { name: "exotic", description: "", function: (), required_features: &["not-a-feature"], tags: Default::default() },
+        ];
+        let (supported, skipped) = split_supported(variants);
+
+        assert_eq!(supported.len(), 1);
+        assert_eq!(supported[0].name, "portable");
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].name, "exotic");
+        assert_eq!(skipped[0].reason, "CPU lacks not-a-feature");
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn test_x86_64_baseline() {
+        // SSE2 is part of the x86_64 baseline
+        assert!(is_supported("sse2"));
+        assert!(missing_features(&["sse2"]).is_empty());
+    }
+}
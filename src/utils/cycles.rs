@@ -1,7 +1,7 @@
 //! CPU Cycle Counter for precise micro-benchmarking.
 //!
 //! This module provides architecture-specific cycle counter implementations
-//! for x86_64 and aarch64.
+//! for x86, x86_64 and aarch64 (the `rdtsc` clock, see `clock`).
 
 /// Read the current CPU cycle counter / timer.
 ///
@@ -23,11 +23,6 @@ pub fn read_cycles() -> u64 {
     {
         read_cycles_aarch64()
     }
-
-    #[cfg(not(any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64")))]
-    {
-        compile_error!("cpu_cycles feature requires x86, x86_64, or aarch64 architecture");
-    }
 }
 
 #[cfg(target_arch = "x86_64")]
@@ -68,6 +63,63 @@ fn read_cycles_aarch64() -> u64 {
     val
 }
 
+/// Intervals timed by `calibrate_frequency` (the median rate is kept)
+const CALIBRATION_ROUNDS: usize = 5;
+
+/// Length of one calibration interval
+const CALIBRATION_INTERVAL: std::time::Duration = std::time::Duration::from_millis(10);
+
+/// Frequency of the counter read by `read_cycles`, in Hz.
+///
+/// On aarch64 the frequency is architectural (`CNTFRQ_EL0`). Elsewhere the
+/// counter is timed against `CLOCK_MONOTONIC_RAW` (not slewed by NTP) over a
+/// few short intervals, and the median rate is returned. `None` if the
+/// counter did not advance.
+pub fn calibrate_frequency() -> Option<f64> {
+    #[cfg(target_arch = "aarch64")]
+    {
+        let frequency: u64;
+        unsafe {
+            core::arch::asm!("mrs {}, cntfrq_el0", out(reg) frequency);
+        }
+        if frequency > 0 {
+            return Some(frequency as f64);
+        }
+    }
+
+    let mut rates: Vec<f64> = (0..CALIBRATION_ROUNDS)
+        .filter_map(|_| {
+            let (start_ns, start) = (monotonic_raw_ns(), read_cycles());
+            while monotonic_raw_ns() - start_ns < CALIBRATION_INTERVAL.as_nanos() as u64 {
+                std::hint::spin_loop();
+            }
+            let (end_ns, end) = (monotonic_raw_ns(), read_cycles());
+            let ticks = end.checked_sub(start).filter(|&t| t > 0)?;
+            Some(ticks as f64 * 1e9 / (end_ns - start_ns) as f64)
+        })
+        .collect();
+    rates.sort_by(f64::total_cmp);
+    rates.get(rates.len() / 2).copied()
+}
+
+/// Nanoseconds of `CLOCK_MONOTONIC_RAW`
+#[cfg(target_os = "linux")]
+fn monotonic_raw_ns() -> u64 {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    unsafe {
+        libc::clock_gettime(libc::CLOCK_MONOTONIC_RAW, &mut ts);
+    }
+    ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64
+}
+
+/// Nanoseconds of the monotonic clock (no raw clock outside Linux)
+#[cfg(not(target_os = "linux"))]
+fn monotonic_raw_ns() -> u64 {
+    use std::sync::OnceLock;
+    static EPOCH: OnceLock<std::time::Instant> = OnceLock::new();
+    EPOCH.get_or_init(std::time::Instant::now).elapsed().as_nanos() as u64
+}
+
 /// Measure cycles for a closure
 #[inline(always)]
 pub fn measure_cycles<F, R>(mut f: F) -> (u64, R)
@@ -102,6 +154,13 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_calibrate_frequency() {
+        // Between 1 MHz (slow generic timers) and 10 GHz
+        let hz = calibrate_frequency().expect("counter should advance");
+        assert!(hz > 1e6 && hz < 1e10, "{} Hz", hz);
+    }
+
     #[test]
     fn test_measure_cycles() {
         let (cycles, result) = measure_cycles(|| {
@@ -0,0 +1,189 @@
+//! Variant selection on the command line.
+//!
+//! Variants are selected by `algorithm/variant` glob patterns (`--variants`,
+//! `--exclude`) and by their tags (`--lang`, `--arch`, `--simd`, `--technique`).
+//! The runner applies the filter before collecting closures, so excluded
+//! variants cost nothing.
+
+use super::tags::{Language, VariantTags};
+
+/// Selection of variants by name pattern and tag.
+///
+/// Patterns have the form `algorithm/variant`, where both parts may contain the
+/// wildcards `*` (any sequence) and `?` (any character); a pattern without `/`
+/// applies to the variant name in every algorithm. A variant is selected if it
+/// matches any `include` pattern (or there are none), no `exclude` pattern,
+/// and every tag criterion.
+///
+/// Unset tag criteria match everything; the value "none" matches variants
+/// without that component (e.g. `--simd none` selects scalar code).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VariantFilter {
+    /// `--variants` patterns
+    pub include: Vec<String>,
+    /// `--exclude` patterns
+    pub exclude: Vec<String>,
+    pub lang: Option<Language>,
+    pub arch: Option<String>,
+    pub simd: Option<String>,
+    pub technique: Option<String>,
+}
+
+impl VariantFilter {
+    /// Whether the filter selects every variant
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether the variant `name` of `algorithm` is selected by the patterns alone
+    pub fn matches_name(&self, algorithm: &str, name: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| pattern_matches(p, algorithm, name)))
+            && !self.exclude.iter().any(|p| pattern_matches(p, algorithm, name))
+    }
+
+    /// Whether a variant with these tags is selected by the tag criteria alone
+    pub fn matches_tags(&self, tags: &VariantTags) -> bool {
+        self.lang.is_none_or(|lang| lang == tags.lang)
+            && component_matches(self.arch.as_deref(), tags.arch)
+            && component_matches(self.simd.as_deref(), tags.simd)
+            && component_matches(self.technique.as_deref(), tags.technique)
+    }
+
+    /// Whether the variant is selected
+    pub fn matches(&self, algorithm: &str, name: &str, tags: &VariantTags) -> bool {
+        self.matches_name(algorithm, name) && self.matches_tags(tags)
+    }
+
+    /// Human-readable summary (e.g. "variants=dot_product/x86_64-*, lang=c")
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.include.is_empty() {
+            parts.push(format!("variants={}", self.include.join(",")));
+        }
+        if !self.exclude.is_empty() {
+            parts.push(format!("exclude={}", self.exclude.join(",")));
+        }
+        if let Some(lang) = self.lang {
+            parts.push(format!("lang={}", lang.as_str()));
+        }
+        for (key, value) in [("arch", &self.arch), ("simd", &self.simd), ("technique", &self.technique)] {
+            if let Some(value) = value {
+                parts.push(format!("{}={}", key, value));
+            }
+        }
+        parts.join(", ")
+    }
+}
+
+fn component_matches(wanted: Option<&str>, actual: Option<&str>) -> bool {
+    match (wanted, actual) {
+        (None, _) => true,
+        (Some(w), None) => w.eq_ignore_ascii_case("none"),
+        (Some(w), Some(a)) => w.eq_ignore_ascii_case(a),
+    }
+}
+
+/// Match an `algorithm/variant` (or bare `variant`) pattern
+fn pattern_matches(pattern: &str, algorithm: &str, name: &str) -> bool {
+    match pattern.split_once('/') {
+        Some((algo_pattern, variant_pattern)) => {
+            glob_matches(algo_pattern, algorithm) && glob_matches(variant_pattern, name)
+        }
+        None => glob_matches(pattern, name),
+    }
+}
+
+/// Glob matching with `*` (any sequence) and `?` (any single character)
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it currently absorbs up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, absorbed)) = backtrack {
+            // Let the last `*` absorb one more character
+            p = star + 1;
+            t = absorbed + 1;
+            backtrack = Some((star, t));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Split a comma-separated list of patterns, dropping empty entries
+pub fn parse_patterns(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("x86_64-*", "x86_64-avx2"));
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("*-asm-*", "x86_64-asm-call"));
+        assert!(glob_matches("c-?witch", "c-switch"));
+        assert!(glob_matches("xoroshiro128++", "xoroshiro128++"));
+        assert!(!glob_matches("x86_64-*", "c-x86_64-sse2"));
+        assert!(!glob_matches("original", "original2"));
+        assert!(!glob_matches("?", ""));
+    }
+
+    #[test]
+    fn test_patterns() {
+        let filter = VariantFilter {
+            include: parse_patterns("dot_product/x86_64-*, */original"),
+            exclude: parse_patterns("*-sse2"),
+            ..Default::default()
+        };
+        assert!(filter.matches_name("dot_product", "x86_64-avx2"));
+        assert!(filter.matches_name("xoroshiro128++", "original"));
+        assert!(!filter.matches_name("dot_product", "x86_64-sse2"));
+        assert!(!filter.matches_name("dot_product", "scalar_opt"));
+        assert!(!filter.matches_name("xoroshiro128++", "x86_64-asm"));
+
+        // Without '/', a pattern applies to variants of every algorithm
+        let bare = VariantFilter { exclude: vec!["c-*".to_string()], ..Default::default() };
+        assert!(bare.matches_name("dot_product", "original"));
+        assert!(!bare.matches_name("elseif_vs_jumptable", "c-switch"));
+    }
+
+    #[test]
+    fn test_tag_matches() {
+        let avx2 = VariantTags::rust().arch("x86_64").simd("avx2");
+        let asm = VariantTags::rust().arch("x86_64").technique("asm");
+        let c = VariantTags::c();
+
+        assert!(VariantFilter::default().matches_tags(&avx2));
+        assert!(VariantFilter::default().is_empty());
+
+        let simd = VariantFilter { simd: Some("AVX2".to_string()), ..Default::default() };
+        assert!(simd.matches_tags(&avx2));
+        assert!(!simd.matches_tags(&asm));
+
+        let scalar = VariantFilter { simd: Some("none".to_string()), ..Default::default() };
+        assert!(!scalar.matches_tags(&avx2));
+        assert!(scalar.matches_tags(&asm));
+
+        let c_asm = VariantFilter { lang: Some(Language::C), technique: Some("asm".to_string()), ..Default::default() };
+        assert!(!c_asm.matches_tags(&asm));
+        assert!(!c_asm.matches_tags(&c));
+        assert_eq!(c_asm.describe(), "lang=c, technique=asm");
+    }
+}
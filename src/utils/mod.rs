@@ -1,29 +1,56 @@
 //! Utility modules for benchmarking and execution.
 
 pub mod bench;
+pub mod cache;
+pub mod clock;
+pub mod config;
 pub mod cpu_affinity;
+pub mod cpu_features;
+pub mod filter;
+pub mod json;
+pub mod params;
+pub mod perf;
+pub mod preflight;
+pub mod report;
 pub mod runner;
+pub mod stats;
+pub mod sysinfo;
+pub mod tags;
+pub mod throughput;
 pub mod timer;
+pub mod toml;
 pub mod tui;
 
-#[cfg(all(feature = "cpu_cycles", not(feature = "use_time")))]
+#[cfg(any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64"))]
 pub mod cycles;
 
 // Re-export commonly used items
 pub use bench::{
-    calculate_std_dev, compute_stats, shuffle, shuffle_with_rng, time_seed, SeededRng,
+    calculate_std_dev, compute_stats, elapsed, now, shuffle, shuffle_with_rng, time_seed, SeededRng,
 };
+pub use clock::ClockKind;
 pub use cpu_affinity::CpuPinGuard;
+pub use filter::VariantFilter;
+pub use params::{Parameter, Params};
+pub use perf::{CounterSample, CounterStats, HwCounter};
+pub use report::{Comparison, ReportEntry, RunReport, Verdict};
+pub use sysinfo::{BuildInfo, MachineInfo};
+pub use tags::{Language, VariantTags};
+pub use throughput::{Work, WorkUnit};
 pub use timer::{calculate_median, measure_variants, TimingConfig, Variant, VariantResult};
 
-#[cfg(any(not(feature = "cpu_cycles"), feature = "use_time"))]
-pub use bench::{elapsed, now};
-#[cfg(all(feature = "cpu_cycles", not(feature = "use_time")))]
+#[cfg(any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64"))]
 pub use cycles::{measure_cycles, read_cycles};
 
 /// C compiler name detected at build time
 pub const C_COMPILER_NAME: Option<&str> = option_env!("C_COMPILER_NAME");
 
+/// rustc version used to build the crate (`rustc --version`)
+pub const RUSTC_VERSION: Option<&str> = option_env!("RUSTC_VERSION");
+
+/// Comma-separated target features enabled at build time
+pub const TARGET_FEATURES: Option<&str> = option_env!("TARGET_FEATURES");
+
 /// Information about an algorithm implementation variant.
 /// Generic over F which is the function signature.
 pub struct VariantInfo<F> {
@@ -33,4 +60,63 @@ pub struct VariantInfo<F> {
     pub description: &'static str,
     /// The specific implementation function
     pub function: F,
+    /// CPU features the implementation needs at runtime (e.g. `&["avx2", "fma"]`)
+    pub required_features: &'static [&'static str],
+    /// Language, architecture, SIMD level and technique
+    pub tags: VariantTags,
+}
+
+impl<F> VariantInfo<F> {
+    /// Required features the running CPU lacks (empty if the variant can run)
+    pub fn missing_features(&self) -> Vec<&'static str> {
+        cpu_features::missing_features(self.required_features)
+    }
+
+    /// Metadata of the variant, without the function
+    pub fn descriptor(&self) -> VariantDescriptor {
+        VariantDescriptor {
+            name: self.name,
+            description: self.description,
+            tags: self.tags,
+            required_features: self.required_features,
+        }
+    }
+}
+
+/// Metadata of a variant independent of its function signature (for listings)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VariantDescriptor {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub tags: VariantTags,
+    pub required_features: &'static [&'static str],
+}
+
+/// A variant that was compiled in but cannot run on this machine
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SkippedVariant {
+    pub name: &'static str,
+    /// Why the variant was skipped (e.g. "CPU lacks avx2, fma")
+    pub reason: String,
+    /// Required features the CPU does not support
+    pub missing_features: Vec<&'static str>,
+}
+
+/// Split variants into those the running CPU supports and those it must skip
+pub fn split_supported<F>(variants: Vec<VariantInfo<F>>) -> (Vec<VariantInfo<F>>, Vec<SkippedVariant>) {
+    let mut supported = Vec::new();
+    let mut skipped = Vec::new();
+    for variant in variants {
+        let missing = variant.missing_features();
+        if missing.is_empty() {
+            supported.push(variant);
+        } else {
+            skipped.push(SkippedVariant {
+                name: variant.name,
+                reason: format!("CPU lacks {}", missing.join(", ")),
+                missing_features: missing,
+            });
+        }
+    }
+    (supported, skipped)
 }
@@ -0,0 +1,129 @@
+//! Benchmark parameters besides the input size.
+//!
+//! An algorithm declares named parameters, each with the set of values worth
+//! benchmarking (e.g. the distribution of the input data, or the alignment of
+//! a buffer). The runner benchmarks every combination of values, and each
+//! combination is shown and exported as `name=value` pairs.
+
+/// A named parameter and the values it is benchmarked at
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Parameter {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Values in benchmark order (the first is the reference)
+    pub values: &'static [&'static str],
+}
+
+/// The value of each parameter of an algorithm for one benchmark
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Params(Vec<(String, String)>);
+
+impl Params {
+    pub fn new(values: Vec<(String, String)>) -> Self {
+        Self(values)
+    }
+
+    /// Value of the parameter `name`
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// `(name, value)` pairs in declaration order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+}
+
+impl std::fmt::Display for Params {
+    /// Pairs separated by commas (e.g. "distribution=sorted, offset=4")
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pairs: Vec<String> = self.iter().map(|(n, v)| format!("{}={}", n, v)).collect();
+        write!(f, "{}", pairs.join(", "))
+    }
+}
+
+/// Every combination of parameter values, the last parameter varying fastest.
+///
+/// `selected` restricts parameters to the listed values (`--param NAME=VALUES`);
+/// values that the parameter does not declare are ignored. Without parameters,
+/// the result is a single empty `Params`.
+pub fn combinations(parameters: &[Parameter], selected: &[(String, Vec<String>)]) -> Vec<Params> {
+    let mut combinations = vec![Vec::new()];
+    for parameter in parameters {
+        let values: Vec<&str> = match selected.iter().find(|(name, _)| name == parameter.name) {
+            Some((_, wanted)) => parameter
+                .values
+                .iter()
+                .copied()
+                .filter(|v| wanted.iter().any(|w| w == v))
+                .collect(),
+            None => parameter.values.to_vec(),
+        };
+        combinations = combinations
+            .into_iter()
+            .flat_map(|prefix: Vec<(String, String)>| {
+                values.iter().map(move |value| {
+                    let mut combination = prefix.clone();
+                    combination.push((parameter.name.to_string(), value.to_string()));
+                    combination
+                })
+            })
+            .collect();
+    }
+    combinations.into_iter().map(Params).collect()
+}
+
+/// Parse `NAME=V1,V2` (`--param`)
+pub fn parse_selection(arg: &str) -> Option<(String, Vec<String>)> {
+    let (name, values) = arg.split_once('=')?;
+    let values: Vec<String> = values
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect();
+    if name.trim().is_empty() || values.is_empty() {
+        return None;
+    }
+    Some((name.trim().to_string(), values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMETERS: &[Parameter] = &[
+        Parameter { name: "distribution", description: "", values: &["random", "sorted"] },
+        Parameter { name: "offset", description: "", values: &["0", "4", "8"] },
+    ];
+
+    #[test]
+    fn test_combinations() {
+        let all = combinations(PARAMETERS, &[]);
+        assert_eq!(all.len(), 6);
+        assert_eq!(all[0].to_string(), "distribution=random, offset=0");
+        assert_eq!(all[5].to_string(), "distribution=sorted, offset=8");
+        assert_eq!(all[4].get("offset"), Some("4"));
+
+        let selected = vec![parse_selection("offset=8,16").unwrap()];
+        let some = combinations(PARAMETERS, &selected);
+        assert_eq!(some.len(), 2);
+        assert!(some.iter().all(|p| p.get("offset") == Some("8")));
+
+        assert_eq!(combinations(&[], &selected), vec![Params::default()]);
+    }
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!(
+            parse_selection("offset = 0, 4"),
+            Some(("offset".to_string(), vec!["0".to_string(), "4".to_string()]))
+        );
+        assert_eq!(parse_selection("offset"), None);
+        assert_eq!(parse_selection("offset="), None);
+    }
+}
@@ -0,0 +1,457 @@
+//! Hardware performance counters via `perf_event_open`.
+//!
+//! This module opens a group of per-thread hardware counters (actual core
+//! cycles, instructions retired, branch misses, L1D and LLC misses) and lets
+//! the `measure!` macro snapshot them around the measured expression.
+//!
+//! Counters are only available on Linux. On other platforms (or when the
+//! kernel refuses access, e.g. `perf_event_paranoid` too high or running in
+//! a VM without a PMU) opening a session fails and benchmarks simply run
+//! without counter data.
+
+use std::cell::{Cell, RefCell};
+
+/// Number of supported hardware counters
+pub const NUM_COUNTERS: usize = 5;
+
+/// A hardware event that can be counted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HwCounter {
+    /// Actual core cycles (affected by frequency scaling, unlike RDTSC)
+    CoreCycles,
+    /// Instructions retired
+    Instructions,
+    /// Mispredicted branch instructions
+    BranchMisses,
+    /// L1 data cache read misses
+    L1dMisses,
+    /// Last-level cache misses
+    LlcMisses,
+}
+
+impl HwCounter {
+    /// All counters, in the order used to index `CounterSample` and `CounterStats`
+    pub const ALL: [HwCounter; NUM_COUNTERS] = [
+        HwCounter::CoreCycles,
+        HwCounter::Instructions,
+        HwCounter::BranchMisses,
+        HwCounter::L1dMisses,
+        HwCounter::LlcMisses,
+    ];
+
+    /// Short name used in tables and exports
+    pub fn name(&self) -> &'static str {
+        match self {
+            HwCounter::CoreCycles => "core-cycles",
+            HwCounter::Instructions => "instructions",
+            HwCounter::BranchMisses => "branch-misses",
+            HwCounter::L1dMisses => "l1d-misses",
+            HwCounter::LlcMisses => "llc-misses",
+        }
+    }
+
+    /// Position of this counter in `HwCounter::ALL`
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Counter deltas for a single measurement (`None` if the counter is unavailable)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CounterSample(pub [Option<u64>; NUM_COUNTERS]);
+
+impl CounterSample {
+    /// Get the delta for one counter
+    pub fn get(&self, counter: HwCounter) -> Option<u64> {
+        self.0[counter.index()]
+    }
+}
+
+/// Per-variant counter averages (per call)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CounterStats {
+    /// Mean value per counter, indexed like `HwCounter::ALL`
+    pub means: [Option<f64>; NUM_COUNTERS],
+    /// Number of samples that carried counter data
+    pub samples: usize,
+}
+
+impl CounterStats {
+    /// Average the given samples. Returns `None` if there are none.
+    pub fn from_samples(samples: &[CounterSample]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut means = [None; NUM_COUNTERS];
+        for (idx, mean) in means.iter_mut().enumerate() {
+            let values: Vec<u64> = samples.iter().filter_map(|s| s.0[idx]).collect();
+            if !values.is_empty() {
+                *mean = Some(values.iter().sum::<u64>() as f64 / values.len() as f64);
+            }
+        }
+
+        Some(Self {
+            means,
+            samples: samples.len(),
+        })
+    }
+
+    /// Get the mean for one counter
+    pub fn get(&self, counter: HwCounter) -> Option<f64> {
+        self.means[counter.index()]
+    }
+
+    /// Instructions per core cycle
+    pub fn ipc(&self) -> Option<f64> {
+        match (self.get(HwCounter::Instructions), self.get(HwCounter::CoreCycles)) {
+            (Some(instr), Some(cycles)) if cycles > 0.0 => Some(instr / cycles),
+            _ => None,
+        }
+    }
+}
+
+// ============================================================================
+// Linux implementation using perf_event_open
+// ============================================================================
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{HwCounter, NUM_COUNTERS};
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_TYPE_HW_CACHE: u32 = 3;
+
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+    const PERF_COUNT_HW_BRANCH_MISSES: u64 = 5;
+
+    // cache id | (op << 8) | (result << 16)
+    const PERF_COUNT_HW_CACHE_L1D_READ_MISS: u64 = 1 << 16;
+
+    const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1 << 0;
+    const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+    const PERF_FORMAT_GROUP: u64 = 1 << 3;
+
+    const ATTR_FLAG_DISABLED: u64 = 1 << 0;
+    const ATTR_FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+    const ATTR_FLAG_EXCLUDE_HV: u64 = 1 << 6;
+
+    const PERF_EVENT_IOC_ENABLE: libc::c_ulong = 0x2400;
+    const PERF_EVENT_IOC_RESET: libc::c_ulong = 0x2403;
+    const PERF_IOC_FLAG_GROUP: libc::c_ulong = 1;
+    const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+    /// `struct perf_event_attr` (PERF_ATTR_SIZE_VER5 layout)
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        type_: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+        config2: u64,
+        branch_sample_type: u64,
+        sample_regs_user: u64,
+        sample_stack_user: u32,
+        clockid: i32,
+        sample_regs_intr: u64,
+        aux_watermark: u32,
+        sample_max_stack: u16,
+        reserved_2: u16,
+    }
+
+    fn event_for(counter: HwCounter) -> (u32, u64) {
+        match counter {
+            HwCounter::CoreCycles => (PERF_TYPE_HARDWARE, PERF_COUNT_HW_CPU_CYCLES),
+            HwCounter::Instructions => (PERF_TYPE_HARDWARE, PERF_COUNT_HW_INSTRUCTIONS),
+            HwCounter::BranchMisses => (PERF_TYPE_HARDWARE, PERF_COUNT_HW_BRANCH_MISSES),
+            HwCounter::L1dMisses => (PERF_TYPE_HW_CACHE, PERF_COUNT_HW_CACHE_L1D_READ_MISS),
+            HwCounter::LlcMisses => (PERF_TYPE_HARDWARE, PERF_COUNT_HW_CACHE_MISSES),
+        }
+    }
+
+    fn open_event(counter: HwCounter, group_fd: i32) -> Option<i32> {
+        let (type_, config) = event_for(counter);
+        let is_leader = group_fd == -1;
+        let attr = PerfEventAttr {
+            type_,
+            size: std::mem::size_of::<PerfEventAttr>() as u32,
+            config,
+            read_format: PERF_FORMAT_GROUP
+                | PERF_FORMAT_TOTAL_TIME_ENABLED
+                | PERF_FORMAT_TOTAL_TIME_RUNNING,
+            flags: ATTR_FLAG_EXCLUDE_KERNEL
+                | ATTR_FLAG_EXCLUDE_HV
+                | if is_leader { ATTR_FLAG_DISABLED } else { 0 },
+            ..Default::default()
+        };
+
+        // pid = 0 (this thread), cpu = -1 (follow the thread across cores)
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_perf_event_open,
+                &attr as *const PerfEventAttr,
+                0 as libc::pid_t,
+                -1 as libc::c_int,
+                group_fd as libc::c_int,
+                PERF_FLAG_FD_CLOEXEC,
+            )
+        };
+        if fd >= 0 {
+            Some(fd as i32)
+        } else {
+            None
+        }
+    }
+
+    /// An open group of counters for the current thread
+    pub struct CounterGroup {
+        leader: i32,
+        /// File descriptors in group read order, with their counter
+        members: Vec<(HwCounter, i32)>,
+    }
+
+    impl CounterGroup {
+        pub fn open(counters: &[HwCounter]) -> Result<Self, String> {
+            let mut group = CounterGroup {
+                leader: -1,
+                members: Vec::new(),
+            };
+
+            for &counter in counters {
+                if let Some(fd) = open_event(counter, group.leader) {
+                    if group.leader == -1 {
+                        group.leader = fd;
+                    }
+                    group.members.push((counter, fd));
+                }
+            }
+
+            if group.leader == -1 {
+                let err = std::io::Error::last_os_error();
+                return Err(format!(
+                    "perf_event_open failed ({}); check /proc/sys/kernel/perf_event_paranoid",
+                    err
+                ));
+            }
+
+            unsafe {
+                libc::ioctl(group.leader, PERF_EVENT_IOC_RESET, PERF_IOC_FLAG_GROUP);
+                libc::ioctl(group.leader, PERF_EVENT_IOC_ENABLE, PERF_IOC_FLAG_GROUP);
+            }
+
+            Ok(group)
+        }
+
+        pub fn counters(&self) -> Vec<HwCounter> {
+            self.members.iter().map(|(c, _)| *c).collect()
+        }
+
+        /// Read all counters. Returns `None` if the group was not scheduled on the PMU.
+        #[inline(always)]
+        pub fn read(&self) -> Option<[Option<u64>; NUM_COUNTERS]> {
+            // Layout: nr, time_enabled, time_running, values[nr]
+            let mut buf = [0u64; 3 + NUM_COUNTERS];
+            let bytes = unsafe {
+                libc::read(
+                    self.leader,
+                    buf.as_mut_ptr() as *mut libc::c_void,
+                    std::mem::size_of_val(&buf),
+                )
+            };
+            if bytes <= 0 || buf[2] == 0 {
+                return None;
+            }
+
+            let nr = (buf[0] as usize).min(self.members.len());
+            let mut values = [None; NUM_COUNTERS];
+            for (i, (counter, _)) in self.members.iter().take(nr).enumerate() {
+                values[counter.index()] = Some(buf[3 + i]);
+            }
+            Some(values)
+        }
+    }
+
+    impl Drop for CounterGroup {
+        fn drop(&mut self) {
+            for (_, fd) in self.members.drain(..).rev() {
+                unsafe {
+                    libc::close(fd);
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Fallback for other platforms
+// ============================================================================
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::{HwCounter, NUM_COUNTERS};
+
+    pub struct CounterGroup;
+
+    impl CounterGroup {
+        pub fn open(_counters: &[HwCounter]) -> Result<Self, String> {
+            Err("hardware counters require Linux perf_event_open".to_string())
+        }
+
+        pub fn counters(&self) -> Vec<HwCounter> {
+            Vec::new()
+        }
+
+        #[inline(always)]
+        pub fn read(&self) -> Option<[Option<u64>; NUM_COUNTERS]> {
+            None
+        }
+    }
+}
+
+// ============================================================================
+// Thread-local session
+// ============================================================================
+
+thread_local! {
+    static GROUP: RefCell<Option<platform::CounterGroup>> = const { RefCell::new(None) };
+    static LAST_SAMPLE: Cell<Option<CounterSample>> = const { Cell::new(None) };
+}
+
+/// Raw counter values captured by `begin()`
+pub type Snapshot = [Option<u64>; NUM_COUNTERS];
+
+/// Open hardware counters for the current thread.
+///
+/// Until `disable()` is called, every `measure!` on this thread records
+/// counter deltas that can be retrieved with `take_last()`.
+///
+/// # Returns
+/// The counters that could actually be opened, or an error message.
+pub fn enable() -> Result<Vec<HwCounter>, String> {
+    let group = platform::CounterGroup::open(&HwCounter::ALL)?;
+    let counters = group.counters();
+    GROUP.with(|g| *g.borrow_mut() = Some(group));
+    LAST_SAMPLE.with(|s| s.set(None));
+    Ok(counters)
+}
+
+/// Close the counters opened by `enable()` on the current thread.
+pub fn disable() {
+    GROUP.with(|g| *g.borrow_mut() = None);
+    LAST_SAMPLE.with(|s| s.set(None));
+}
+
+/// Check whether counters are currently open on this thread.
+pub fn is_enabled() -> bool {
+    GROUP.with(|g| g.borrow().is_some())
+}
+
+/// Snapshot counters before a measurement (no-op when disabled).
+#[inline(always)]
+pub fn begin() -> Option<Snapshot> {
+    GROUP.with(|g| g.borrow().as_ref().and_then(|group| group.read()))
+}
+
+/// Snapshot counters after a measurement and store the delta for `take_last()`.
+#[inline(always)]
+pub fn end(start: Option<Snapshot>) {
+    let Some(start) = start else {
+        return;
+    };
+    let Some(stop) = GROUP.with(|g| g.borrow().as_ref().and_then(|group| group.read())) else {
+        return;
+    };
+
+    let mut sample = CounterSample::default();
+    for (idx, value) in sample.0.iter_mut().enumerate() {
+        if let (Some(a), Some(b)) = (start[idx], stop[idx]) {
+            *value = Some(b.saturating_sub(a));
+        }
+    }
+    LAST_SAMPLE.with(|s| s.set(Some(sample)));
+}
+
+/// Take the counter deltas recorded by the most recent `measure!` on this thread.
+pub fn take_last() -> Option<CounterSample> {
+    LAST_SAMPLE.with(|s| s.take())
+}
+
+// ============================================================================
+// Core cycle clock (`--clock perf`)
+// ============================================================================
+
+thread_local! {
+    static CORE_CYCLES: RefCell<Option<platform::CounterGroup>> = const { RefCell::new(None) };
+}
+
+/// Open a core cycle counter for the current thread, read by `read_core_cycles`
+/// (does nothing if it is already open).
+pub fn open_core_cycles() -> Result<(), String> {
+    if CORE_CYCLES.with(|c| c.borrow().is_some()) {
+        return Ok(());
+    }
+    let group = platform::CounterGroup::open(&[HwCounter::CoreCycles])?;
+    if group.read().and_then(|values| values[HwCounter::CoreCycles.index()]).is_none() {
+        return Err("the core cycle counter is not scheduled on the PMU".to_string());
+    }
+    CORE_CYCLES.with(|c| *c.borrow_mut() = Some(group));
+    Ok(())
+}
+
+/// User-space core cycles of the current thread since `open_core_cycles`
+/// (0 if the counter is not open or could not be read).
+#[inline(always)]
+pub fn read_core_cycles() -> u64 {
+    CORE_CYCLES.with(|c| {
+        c.borrow()
+            .as_ref()
+            .and_then(|group| group.read())
+            .and_then(|values| values[HwCounter::CoreCycles.index()])
+            .unwrap_or(0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_stats_from_samples() {
+        let mut a = CounterSample::default();
+        a.0[HwCounter::CoreCycles.index()] = Some(100);
+        a.0[HwCounter::Instructions.index()] = Some(200);
+        let mut b = a;
+        b.0[HwCounter::CoreCycles.index()] = Some(300);
+
+        let stats = CounterStats::from_samples(&[a, b]).unwrap();
+        assert_eq!(stats.samples, 2);
+        assert_eq!(stats.get(HwCounter::CoreCycles), Some(200.0));
+        assert_eq!(stats.get(HwCounter::Instructions), Some(200.0));
+        assert_eq!(stats.get(HwCounter::BranchMisses), None);
+        assert_eq!(stats.ipc(), Some(1.0));
+        assert!(CounterStats::from_samples(&[]).is_none());
+    }
+
+    #[test]
+    fn test_session_records_sample() {
+        // Counters may be unavailable (containers, VMs, paranoid settings)
+        if enable().is_err() {
+            return;
+        }
+        let (_, value) = crate::measure!((0..1000u64).sum::<u64>());
+        assert_eq!(value, 499500);
+        let _ = take_last();
+        disable();
+        assert!(!is_enabled());
+        assert!(take_last().is_none());
+    }
+}
@@ -0,0 +1,326 @@
+//! System noise check before benchmarking (Linux).
+//!
+//! Measurements of the selected `clock` are only comparable between runs
+//! when the machine is in the same state: a `powersave` governor, turbo,
+//! a busy SMT sibling or other runnable tasks change them for reasons that
+//! have nothing to do with the code. `Preflight::run` reads these settings
+//! from sysfs and procfs; the CLI prints them before benchmarking and, with
+//! `--strict`, refuses to run when any of them is a warning.
+
+#[cfg(any(target_os = "linux", test))]
+use super::clock;
+#[cfg(any(target_os = "linux", test))]
+use super::cpu_affinity::parse_cpu_list;
+
+/// Outcome of one check
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// Good for stable measurements
+    Ok,
+    /// Could be better, but not a source of bogus results by itself
+    Note,
+    /// Makes the measurements untrustworthy (`--strict` refuses to run)
+    Warning,
+    /// The setting could not be read (e.g. no cpufreq in a virtual machine)
+    Unknown,
+}
+
+impl Status {
+    /// Label printed in front of the check
+    pub fn label(&self) -> &'static str {
+        match self {
+            Status::Ok => "OK",
+            Status::Note => "NOTE",
+            Status::Warning => "WARN",
+            Status::Unknown => "N/A",
+        }
+    }
+}
+
+/// One setting of the machine and how it affects measurements
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+}
+
+#[cfg(any(target_os = "linux", test))]
+impl Check {
+    fn new(name: &'static str, status: Status, detail: impl Into<String>) -> Self {
+        Self { name, status, detail: detail.into() }
+    }
+}
+
+/// Result of all checks, for the cores the benchmark will be pinned to
+#[derive(Clone, Debug, Default)]
+pub struct Preflight {
+    /// Empty if the core is unknown
+    pub cores: Vec<usize>,
+    /// Empty on platforms other than Linux
+    pub checks: Vec<Check>,
+}
+
+impl Preflight {
+    /// Check the machine state for benchmarks pinned to `cores`
+    pub fn run(cores: &[usize]) -> Self {
+        Self { cores: cores.to_vec(), checks: platform::checks(cores) }
+    }
+
+    pub fn warnings(&self) -> Vec<&Check> {
+        self.checks.iter().filter(|c| c.status == Status::Warning).collect()
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        self.checks.iter().any(|c| c.status == Status::Warning)
+    }
+}
+
+/// Load average above which other tasks are likely to run during the benchmark
+#[cfg(any(target_os = "linux", test))]
+const MAX_LOAD: f64 = 1.0;
+
+/// CPU frequency governor of `core` (`scaling_governor`)
+#[cfg(any(target_os = "linux", test))]
+fn check_governor(governor: Option<&str>) -> Check {
+    match governor.map(str::trim) {
+        None => Check::new("governor", Status::Unknown, "no cpufreq driver (virtual machine or fixed frequency)"),
+        Some("performance") => Check::new("governor", Status::Ok, "performance"),
+        Some(governor) => Check::new(
+            "governor",
+            Status::Warning,
+            format!(
+                "{}: the clock follows the load, so results depend on what ran before \
+                 (cpupower frequency-set -g performance)",
+                governor
+            ),
+        ),
+    }
+}
+
+/// Turbo / boost state, from intel_pstate's `no_turbo` or cpufreq's `boost`
+#[cfg(any(target_os = "linux", test))]
+fn check_boost(no_turbo: Option<&str>, boost: Option<&str>) -> Check {
+    let enabled = match (no_turbo.map(str::trim), boost.map(str::trim)) {
+        (Some(no_turbo), _) => Some((no_turbo == "0", "echo 1 > /sys/devices/system/cpu/intel_pstate/no_turbo")),
+        (None, Some(boost)) => Some((boost == "1", "echo 0 > /sys/devices/system/cpu/cpufreq/boost")),
+        (None, None) => None,
+    };
+    match enabled {
+        None => Check::new("turbo", Status::Unknown, "no boost control found"),
+        Some((false, _)) => Check::new("turbo", Status::Ok, "disabled"),
+        Some((true, fix)) => Check::new(
+            "turbo",
+            Status::Warning,
+            format!("enabled: the clock depends on temperature and on the number of busy cores ({})", fix),
+        ),
+    }
+}
+
+/// Other hardware threads of the physical core of `core`
+#[cfg(any(target_os = "linux", test))]
+fn check_smt(core: Option<usize>, siblings: Option<&str>, isolated: &[usize]) -> Check {
+    let (Some(core), Some(siblings)) = (core, siblings) else {
+        return Check::new("smt", Status::Unknown, "topology of the pinned core unknown");
+    };
+    let siblings: Vec<usize> = parse_cpu_list(siblings).into_iter().filter(|&c| c != core).collect();
+    let names = cpu_names(&siblings);
+    if siblings.is_empty() {
+        Check::new("smt", Status::Ok, format!("cpu{} has no SMT sibling", core))
+    } else if siblings.iter().all(|c| isolated.contains(c)) {
+        Check::new("smt", Status::Ok, format!("sibling {} of cpu{} is isolated", names, core))
+    } else {
+        Check::new(
+            "smt",
+            Status::Warning,
+            format!(
+                "cpu{} shares its physical core with {}: anything running there slows the benchmark \
+                 (isolate the sibling or disable SMT)",
+                core, names
+            ),
+        )
+    }
+}
+
+/// `isolcpus` / `nohz_full` for `core`
+#[cfg(any(target_os = "linux", test))]
+fn check_isolation(core: Option<usize>, isolated: &[usize], nohz_full: &[usize]) -> Check {
+    let Some(core) = core else {
+        return Check::new("isolation", Status::Unknown, "pinned core unknown");
+    };
+    match (isolated.contains(&core), nohz_full.contains(&core)) {
+        (true, true) => Check::new("isolation", Status::Ok, format!("cpu{} is isolated and tickless (nohz_full)", core)),
+        (true, false) => Check::new(
+            "isolation",
+            Status::Ok,
+            format!("cpu{} is isolated (timer ticks remain without nohz_full)", core),
+        ),
+        (false, _) if isolated.is_empty() => Check::new(
+            "isolation",
+            Status::Note,
+            format!("no isolated cores: the scheduler may run other tasks on cpu{} (isolcpus=, nohz_full=)", core),
+        ),
+        (false, _) => Check::new(
+            "isolation",
+            Status::Note,
+            format!("cpu{} is not isolated; isolated: {}", core, cpu_names(isolated)),
+        ),
+    }
+}
+
+/// One-minute load average (`/proc/loadavg`)
+#[cfg(any(target_os = "linux", test))]
+fn check_load(loadavg: Option<&str>) -> Check {
+    let load = loadavg.and_then(|l| l.split_whitespace().next()).and_then(|l| l.parse::<f64>().ok());
+    match load {
+        None => Check::new("load", Status::Unknown, "load average unavailable"),
+        Some(load) if load < MAX_LOAD => Check::new("load", Status::Ok, format!("{:.2}", load)),
+        Some(load) => Check::new(
+            "load",
+            Status::Warning,
+            format!("{:.2}: other tasks are running and may preempt the benchmark", load),
+        ),
+    }
+}
+
+/// Whether the TSC ticks at a fixed rate, also in idle states (`/proc/cpuinfo` flags)
+#[cfg(any(target_os = "linux", test))]
+fn check_tsc(flags: Option<&str>) -> Check {
+    if cfg!(target_arch = "aarch64") {
+        return Check::new("tsc", Status::Ok, "generic timer counter (fixed frequency)");
+    }
+    let Some(flags) = flags.filter(|_| cfg!(target_arch = "x86_64")) else {
+        return Check::new("tsc", Status::Unknown, "cycle counter properties unknown");
+    };
+    let has = |flag: &str| flags.split_whitespace().any(|f| f == flag);
+    let missing: Vec<&str> = ["constant_tsc", "nonstop_tsc"].into_iter().filter(|f| !has(f)).collect();
+    if missing.is_empty() {
+        return Check::new("tsc", Status::Ok, "constant_tsc, nonstop_tsc");
+    }
+    // Only the rdtsc and rdtscp clocks read the TSC directly
+    let status = if clock::selected().reads_tsc() { Status::Warning } else { Status::Note };
+    Check::new(
+        "tsc",
+        status,
+        format!(
+            "no {}: the TSC rate follows the core clock or stops in idle states, so cycle counts drift",
+            missing.join(", ")
+        ),
+    )
+}
+
+/// "cpu3" or "cpu3, cpu7"
+#[cfg(any(target_os = "linux", test))]
+fn cpu_names(cpus: &[usize]) -> String {
+    cpus.iter().map(|c| format!("cpu{}", c)).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+
+    fn read(path: &str) -> Option<String> {
+        std::fs::read_to_string(path).ok()
+    }
+
+    fn cpu_list(path: &str) -> Vec<usize> {
+        read(path).map(|l| parse_cpu_list(&l)).unwrap_or_default()
+    }
+
+    pub fn checks(cores: &[usize]) -> Vec<Check> {
+        let isolated = cpu_list("/sys/devices/system/cpu/isolated");
+        let nohz_full = cpu_list("/sys/devices/system/cpu/nohz_full");
+        let flags = read("/proc/cpuinfo").and_then(|info| {
+            info.lines()
+                .find(|l| l.starts_with("flags"))
+                .and_then(|l| l.split_once(':'))
+                .map(|(_, flags)| flags.to_string())
+        });
+
+        // Per-core checks once for each core (the governor detail names the core when there are several)
+        let per_core: Vec<Option<usize>> = if cores.is_empty() { vec![None] } else { cores.iter().copied().map(Some).collect() };
+        let mut checks = Vec::new();
+        for &core in &per_core {
+            let cpu_dir = |file: &str| core.and_then(|c| read(&format!("/sys/devices/system/cpu/cpu{}/{}", c, file)));
+            let mut governor = check_governor(cpu_dir("cpufreq/scaling_governor").as_deref());
+            if let (Some(core), true) = (core, per_core.len() > 1) {
+                governor.detail = format!("cpu{}: {}", core, governor.detail);
+            }
+            checks.push(governor);
+            checks.push(check_smt(core, cpu_dir("topology/thread_siblings_list").as_deref(), &isolated));
+            checks.push(check_isolation(core, &isolated, &nohz_full));
+        }
+        checks.push(check_boost(
+            read("/sys/devices/system/cpu/intel_pstate/no_turbo").as_deref(),
+            read("/sys/devices/system/cpu/cpufreq/boost").as_deref(),
+        ));
+        checks.push(check_load(read("/proc/loadavg").as_deref()));
+        checks.push(check_tsc(flags.as_deref()));
+        checks
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::*;
+
+    pub fn checks(_cores: &[usize]) -> Vec<Check> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frequency_checks() {
+        assert_eq!(check_governor(Some("performance\n")).status, Status::Ok);
+        assert_eq!(check_governor(Some("powersave\n")).status, Status::Warning);
+        assert_eq!(check_governor(None).status, Status::Unknown);
+
+        assert_eq!(check_boost(Some("1\n"), None).status, Status::Ok);
+        assert_eq!(check_boost(Some("0\n"), Some("0")).status, Status::Warning);
+        assert_eq!(check_boost(None, Some("1\n")).status, Status::Warning);
+        assert_eq!(check_boost(None, None).status, Status::Unknown);
+    }
+
+    #[test]
+    fn test_core_checks() {
+        assert_eq!(check_smt(Some(3), Some("3\n"), &[]).status, Status::Ok);
+        let shared = check_smt(Some(3), Some("3,7\n"), &[]);
+        assert_eq!(shared.status, Status::Warning);
+        assert!(shared.detail.contains("cpu7"), "{}", shared.detail);
+        assert_eq!(check_smt(Some(3), Some("3,7"), &[7]).status, Status::Ok);
+        assert_eq!(check_smt(None, Some("3,7"), &[]).status, Status::Unknown);
+
+        assert_eq!(check_isolation(Some(2), &[2, 3], &[2, 3]).status, Status::Ok);
+        assert_eq!(check_isolation(Some(0), &[2, 3], &[]).status, Status::Note);
+        assert_eq!(check_isolation(Some(0), &[], &[]).status, Status::Note);
+    }
+
+    #[test]
+    fn test_system_checks() {
+        assert_eq!(check_load(Some("0.52 0.35 0.30 2/73 5758\n")).status, Status::Ok);
+        assert_eq!(check_load(Some("3.10 2.00 1.00 5/73 5758\n")).status, Status::Warning);
+        assert_eq!(check_load(Some("")).status, Status::Unknown);
+
+        if cfg!(target_arch = "x86_64") {
+            assert_eq!(check_tsc(Some("fpu tsc constant_tsc nonstop_tsc avx2")).status, Status::Ok);
+            let drifting = check_tsc(Some("fpu tsc avx2"));
+            assert_ne!(drifting.status, Status::Ok);
+            assert!(drifting.detail.contains("constant_tsc, nonstop_tsc"), "{}", drifting.detail);
+        }
+    }
+
+    #[test]
+    fn test_preflight_warnings() {
+        let preflight = Preflight {
+            cores: vec![0],
+            checks: vec![check_governor(Some("powersave")), check_load(Some("0.1"))],
+        };
+        assert!(preflight.has_warnings());
+        assert_eq!(preflight.warnings().len(), 1);
+        assert_eq!(preflight.warnings()[0].name, "governor");
+    }
+}
@@ -0,0 +1,706 @@
+//! Benchmark run snapshots, JSON export and regression comparison.
+//!
+//! A report stores every `VariantResult` of a run (per algorithm, input size
+//! and parameter values) together with the run configuration, machine and build metadata.
+//! The same document is used for `--json` exports and `--save-baseline`
+//! snapshots; a later run can be compared against it to get per-variant
+//! deltas with a significance verdict (Welch's t-test).
+
+use std::time::Duration;
+
+use super::bench::{counter_frequency, unit_name, TimerOverhead};
+use super::cache::CacheMode;
+use super::clock::{self, ClockKind};
+use super::json::JsonValue;
+use super::params::Params;
+use super::perf::{CounterStats, HwCounter, NUM_COUNTERS};
+use super::stats::{welch_t_test, SpeedupEstimate, Summary};
+use super::sysinfo::{BuildInfo, MachineInfo};
+use super::timer::{Batching, CoreMean, PinStrategy, TimingConfig, VariantResult};
+use super::tags::{Language, VariantTags};
+use super::throughput::{Work, WorkUnit};
+
+/// Report format version, bumped on incompatible changes
+pub const FORMAT_VERSION: u64 = 2;
+
+/// Significance level used for comparison verdicts
+pub const DEFAULT_ALPHA: f64 = 0.05;
+
+/// One measured variant of one algorithm at one input size and set of parameter values
+#[derive(Clone, Debug)]
+pub struct ReportEntry {
+    pub algorithm: String,
+    pub input_size: usize,
+    pub params: Params,
+    /// Work done by one call, the throughput is `amount / result.avg_nanos_f64`
+    pub work: Vec<Work>,
+    pub result: VariantResult,
+    /// Relative error of `result_sample` against the baseline variant
+    pub relative_error: Option<f64>,
+}
+
+/// A complete benchmark run with its metadata
+#[derive(Clone, Debug)]
+pub struct RunReport {
+    /// Unix timestamp (seconds) of the run
+    pub timestamp: u64,
+    /// Seed used for the task schedule
+    pub seed: u64,
+    /// Measurement unit of all values ("cycles", "ns", ...)
+    pub unit: String,
+    /// Calibrated frequency of the timestamp counter in Hz (`rdtsc` and `rdtscp` clocks only)
+    pub counter_frequency_hz: Option<f64>,
+    /// Runs, warmup, pinning and counter configuration
+    pub timing: TimingConfig,
+    /// Whether 1% extremes were trimmed before computing statistics
+    pub filter_outliers: bool,
+    /// Calibrated cost of an empty measurement, if known
+    pub timer_overhead: Option<TimerOverhead>,
+    /// Machine the run was measured on
+    pub machine: MachineInfo,
+    /// Binary that measured the run
+    pub build: BuildInfo,
+    pub entries: Vec<ReportEntry>,
+}
+
+impl RunReport {
+    /// Create a report for a run on the current machine
+    pub fn new(seed: u64, timing: &TimingConfig, filter_outliers: bool, entries: Vec<ReportEntry>) -> Self {
+        Self {
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            seed,
+            unit: unit_name().to_string(),
+            counter_frequency_hz: clock::selected().reads_tsc().then(counter_frequency).flatten(),
+            timing: timing.clone(),
+            filter_outliers,
+            timer_overhead: None,
+            machine: MachineInfo::detect(),
+            build: BuildInfo::current(),
+            entries,
+        }
+    }
+
+    /// Find the entry for a given algorithm, size, parameter values and variant
+    pub fn find(&self, algorithm: &str, input_size: usize, params: &Params, variant: &str) -> Option<&ReportEntry> {
+        self.entries.iter().find(|e| {
+            e.algorithm == algorithm && e.input_size == input_size && e.params == *params && e.result.name == variant
+        })
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        let entries: Vec<JsonValue> = self
+            .entries
+            .iter()
+            .map(|e| {
+                JsonValue::object()
+                    .with("algorithm", e.algorithm.as_str())
+                    .with("input_size", e.input_size)
+                    .with("params", params_to_json(&e.params))
+                    .with("work", work_to_json(&e.work))
+                    .with("result", variant_result_to_json(&e.result))
+                    .with("relative_error", e.relative_error)
+            })
+            .collect();
+
+        let config = JsonValue::object()
+            .with("runs_per_variant", self.timing.runs_per_variant)
+            .with("warmup_iterations", self.timing.warmup_iterations)
+            .with("pin_strategy", self.timing.pin_strategy.as_str())
+            .with("hw_counters", self.timing.hw_counters)
+            .with("target_precision", self.timing.target_precision)
+            .with(
+                "time_budget_s",
+                self.timing.is_adaptive().then(|| self.timing.effective_time_budget().as_secs_f64()),
+            )
+            .with("max_runs_per_variant", self.timing.max_runs_per_variant)
+            .with("subtract_overhead", self.timing.subtract_overhead)
+            .with("batching", self.timing.batching.as_string())
+            .with("cache", self.timing.cache.as_str())
+            .with("clock", self.timing.clock.as_str())
+            .with("cores", self.timing.cores.clone())
+            .with("filter_outliers", self.filter_outliers);
+
+        let timer_overhead = self.timer_overhead.map(|o| {
+            JsonValue::object()
+                .with("median", o.median)
+                .with("noise_floor", o.noise_floor)
+                .with("samples", o.samples)
+        });
+
+        JsonValue::object()
+            .with("format_version", FORMAT_VERSION)
+            .with("timestamp", self.timestamp)
+            .with("seed", self.seed)
+            .with("unit", self.unit.as_str())
+            .with("counter_frequency_hz", self.counter_frequency_hz)
+            .with("config", config)
+            .with("timer_overhead", timer_overhead)
+            .with("machine", self.machine.to_json())
+            .with("build", self.build.to_json())
+            .with("entries", entries)
+    }
+
+    pub fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let version = value.get("format_version").and_then(|v| v.as_u64()).unwrap_or(0);
+        if version == 0 || version > FORMAT_VERSION {
+            return Err(format!(
+                "Unsupported report format version {} (supported: 1 to {})",
+                version, FORMAT_VERSION
+            ));
+        }
+
+        let entries = value
+            .get("entries")
+            .and_then(|v| v.as_array())
+            .ok_or("Missing 'entries' array")?
+            .iter()
+            .map(|e| {
+                Ok(ReportEntry {
+                    algorithm: e
+                        .get("algorithm")
+                        .and_then(|v| v.as_str())
+                        .ok_or("Entry without 'algorithm'")?
+                        .to_string(),
+                    input_size: e.get("input_size").and_then(|v| v.as_u64()).unwrap_or(0) as usize,
+                    params: params_from_json(e.get("params")),
+                    work: work_from_json(e.get("work")),
+                    result: variant_result_from_json(e.get("result").ok_or("Entry without 'result'")?)?,
+                    relative_error: e.get("relative_error").and_then(|v| v.as_f64()),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self {
+            timestamp: value.get("timestamp").and_then(|v| v.as_u64()).unwrap_or(0),
+            seed: value.get("seed").and_then(|v| v.as_u64()).unwrap_or(0),
+            unit: value
+                .get("unit")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            counter_frequency_hz: value.get("counter_frequency_hz").and_then(|v| v.as_f64()),
+            timing: timing_from_json(value),
+            filter_outliers: value
+                .get("config")
+                .and_then(|c| c.get("filter_outliers"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            timer_overhead: value.get("timer_overhead").filter(|o| !o.is_null()).map(|o| {
+                let number = |key: &str| o.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+                TimerOverhead {
+                    median: number("median"),
+                    noise_floor: number("noise_floor"),
+                    samples: number("samples") as usize,
+                }
+            }),
+            machine: value.get("machine").map(MachineInfo::from_json).unwrap_or_default(),
+            build: value.get("build").map(BuildInfo::from_json).unwrap_or_default(),
+            entries,
+        })
+    }
+
+    /// Nanoseconds per unit of the report's values (`None` for cycles without a calibrated frequency)
+    pub fn nanos_per_unit(&self) -> Option<f64> {
+        if self.unit == "ns" {
+            Some(1.0)
+        } else {
+            self.counter_frequency_hz.filter(|&hz| hz > 0.0).map(|hz| 1e9 / hz)
+        }
+    }
+
+    /// Write the report as JSON
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json().to_pretty_string())
+    }
+
+    /// Load a report previously written with `save`
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let value = JsonValue::parse(&text).map_err(|e| format!("{}: {}", path, e))?;
+        Self::from_json(&value).map_err(|e| format!("{}: {}", path, e))
+    }
+}
+
+/// Read the run configuration (format 1 only stored `runs_per_variant` at top level)
+fn timing_from_json(value: &JsonValue) -> TimingConfig {
+    let defaults = TimingConfig::default();
+    let config = value.get("config").unwrap_or(value);
+    let number = |key: &str, default: usize| {
+        config.get(key).and_then(|v| v.as_u64()).map(|v| v as usize).unwrap_or(default)
+    };
+    TimingConfig {
+        runs_per_variant: number("runs_per_variant", defaults.runs_per_variant),
+        warmup_iterations: number("warmup_iterations", defaults.warmup_iterations),
+        pin_strategy: config
+            .get("pin_strategy")
+            .and_then(|v| v.as_str())
+            .and_then(PinStrategy::parse)
+            .unwrap_or(defaults.pin_strategy),
+        hw_counters: config.get("hw_counters").and_then(|v| v.as_bool()).unwrap_or(false),
+        target_precision: config.get("target_precision").and_then(|v| v.as_f64()),
+        time_budget: config
+            .get("time_budget_s")
+            .and_then(|v| v.as_f64())
+            .map(Duration::from_secs_f64),
+        max_runs_per_variant: number("max_runs_per_variant", defaults.max_runs_per_variant),
+        subtract_overhead: config.get("subtract_overhead").and_then(|v| v.as_bool()).unwrap_or(false),
+        batching: config
+            .get("batching")
+            .and_then(|v| v.as_str())
+            .and_then(Batching::parse)
+            .unwrap_or(Batching::Off),
+        cache: config
+            .get("cache")
+            .and_then(|v| v.as_str())
+            .and_then(CacheMode::parse)
+            .unwrap_or_default(),
+        cores: config
+            .get("cores")
+            .and_then(|v| v.as_array())
+            .map(|cores| cores.iter().filter_map(|c| c.as_u64()).map(|c| c as usize).collect())
+            .unwrap_or_default(),
+        // Reports from before `--clock` measured nanoseconds with the system clock
+        // and cycles with the timestamp counter
+        clock: config
+            .get("clock")
+            .and_then(|v| v.as_str())
+            .and_then(ClockKind::parse)
+            .unwrap_or(match value.get("unit").and_then(|v| v.as_str()) {
+                Some("ns") => ClockKind::Monotonic,
+                _ => ClockKind::Rdtsc,
+            }),
+    }
+}
+
+/// Relative error of a result value against a reference value.
+///
+/// Returns 0 when either value is missing (algorithms without meaningful results).
+pub fn relative_error(value: Option<f64>, reference: Option<f64>) -> f64 {
+    match (value, reference) {
+        (Some(res), Some(base)) => {
+            let diff = (res - base).abs();
+            if base.abs() > 1e-9 { diff / base.abs() } else { diff }
+        }
+        _ => 0.0,
+    }
+}
+
+/// Coefficient of variation (std dev / mean)
+pub fn coefficient_of_variation(r: &VariantResult) -> f64 {
+    if r.avg_nanos_f64 > 0.0 {
+        r.std_dev_f64 / r.avg_nanos_f64
+    } else {
+        0.0
+    }
+}
+
+fn params_to_json(params: &Params) -> JsonValue {
+    params.iter().fold(JsonValue::object(), |object, (name, value)| object.with(name, value))
+}
+
+/// Parameter values of an entry (none in reports written before parameters existed)
+fn params_from_json(value: Option<&JsonValue>) -> Params {
+    match value {
+        Some(JsonValue::Object(fields)) => Params::new(
+            fields
+                .iter()
+                .filter_map(|(name, value)| Some((name.clone(), value.as_str()?.to_string())))
+                .collect(),
+        ),
+        _ => Params::default(),
+    }
+}
+
+fn work_to_json(work: &[Work]) -> JsonValue {
+    work.iter().fold(JsonValue::object(), |object, w| object.with(w.unit.as_str(), w.amount))
+}
+
+/// Work per call of an entry (none in older reports, or for algorithms without throughput)
+fn work_from_json(value: Option<&JsonValue>) -> Vec<Work> {
+    match value {
+        Some(JsonValue::Object(fields)) => fields
+            .iter()
+            .filter_map(|(unit, amount)| Some(Work { unit: WorkUnit::parse(unit)?, amount: amount.as_u64()? }))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn variant_result_to_json(r: &VariantResult) -> JsonValue {
+    let counters = r.counters.as_ref().map(|c| {
+        HwCounter::ALL.iter().fold(
+            JsonValue::object().with("samples", c.samples),
+            |obj, counter| obj.with(counter.name(), c.get(*counter)),
+        )
+    });
+
+    JsonValue::object()
+        .with("name", r.name.as_str())
+        .with("description", r.description.as_str())
+        .with("avg", r.avg_nanos_f64)
+        .with("median", r.median_time.as_nanos() as u64)
+        .with("min", r.min_time.as_nanos() as u64)
+        .with("max", r.max_time.as_nanos() as u64)
+        .with("std_dev", r.std_dev_f64)
+        .with("cv", coefficient_of_variation(r))
+        .with("iterations", r.iterations)
+        .with("batch_size", r.batch_size)
+        .with("tags", JsonValue::object()
+            .with("lang", r.tags.lang.as_str())
+            .with("arch", r.tags.arch)
+            .with("simd", r.tags.simd)
+            .with("technique", r.tags.technique))
+        .with("result_sample", r.result_sample)
+        .with("counters", counters)
+        .with("below_noise_floor", r.below_noise_floor)
+        .with("mean_ci", r.mean_ci.map(interval_to_json))
+        .with("median_ci", r.median_ci.map(interval_to_json))
+        .with("speedup", r.speedup.as_ref().map(|s| {
+            JsonValue::object()
+                .with("value", s.speedup)
+                .with("ci", s.ci.map(interval_to_json))
+                .with("p_value", s.p_value)
+        }))
+        .with("per_core", r.per_core.iter().map(|c| {
+            JsonValue::object()
+                .with("core", c.core)
+                .with("mean", c.mean)
+                .with("samples", c.samples)
+        }).collect::<Vec<_>>())
+}
+
+fn interval_to_json((low, high): (f64, f64)) -> JsonValue {
+    JsonValue::Array(vec![low.into(), high.into()])
+}
+
+fn interval_from_json(value: Option<&JsonValue>) -> Option<(f64, f64)> {
+    match value?.as_array()? {
+        [low, high] => Some((low.as_f64()?, high.as_f64()?)),
+        _ => None,
+    }
+}
+
+fn variant_result_from_json(value: &JsonValue) -> Result<VariantResult, String> {
+    let duration = |key: &str| {
+        Duration::from_nanos(value.get(key).and_then(|v| v.as_u64()).unwrap_or(0))
+    };
+    let avg = value.get("avg").and_then(|v| v.as_f64()).ok_or("Result without 'avg'")?;
+    let std_dev = value.get("std_dev").and_then(|v| v.as_f64()).unwrap_or(0.0);
+
+    let counters = value.get("counters").filter(|c| !c.is_null()).map(|c| {
+        let mut means = [None; NUM_COUNTERS];
+        for counter in HwCounter::ALL {
+            means[counter.index()] = c.get(counter.name()).and_then(|v| v.as_f64());
+        }
+        CounterStats {
+            means,
+            samples: c.get("samples").and_then(|v| v.as_u64()).unwrap_or(0) as usize,
+        }
+    });
+
+    Ok(VariantResult {
+        name: value
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or("Result without 'name'")?
+            .to_string(),
+        description: value
+            .get("description")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        avg_time: Duration::from_nanos(avg as u64),
+        avg_nanos_f64: avg,
+        median_time: duration("median"),
+        min_time: duration("min"),
+        max_time: duration("max"),
+        std_dev: Duration::from_nanos(std_dev as u64),
+        std_dev_f64: std_dev,
+        iterations: value.get("iterations").and_then(|v| v.as_u64()).unwrap_or(0) as usize,
+        result_sample: value.get("result_sample").and_then(|v| v.as_f64()),
+        counters,
+        mean_ci: interval_from_json(value.get("mean_ci")),
+        median_ci: interval_from_json(value.get("median_ci")),
+        speedup: value.get("speedup").filter(|s| !s.is_null()).and_then(|s| {
+            Some(SpeedupEstimate {
+                speedup: s.get("value")?.as_f64()?,
+                ci: interval_from_json(s.get("ci")),
+                p_value: s.get("p_value").and_then(|v| v.as_f64()),
+            })
+        }),
+        samples: Vec::new(),
+        below_noise_floor: value.get("below_noise_floor").and_then(|v| v.as_bool()).unwrap_or(false),
+        batch_size: value.get("batch_size").and_then(|v| v.as_u64()).unwrap_or(1) as usize,
+        // Only the language is restored: it is all that display and comparison need
+        tags: VariantTags {
+            lang: value
+                .get("tags")
+                .and_then(|t| t.get("lang"))
+                .and_then(|v| v.as_str())
+                .and_then(Language::parse)
+                .unwrap_or_default(),
+            ..VariantTags::default()
+        },
+        per_core: value
+            .get("per_core")
+            .and_then(|v| v.as_array())
+            .map(|cores| {
+                cores
+                    .iter()
+                    .filter_map(|c| {
+                        Some(CoreMean {
+                            core: c.get("core")?.as_u64()? as usize,
+                            mean: c.get("mean")?.as_f64()?,
+                            samples: c.get("samples").and_then(|v| v.as_u64()).unwrap_or(0) as usize,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+// ============================================================================
+// Comparison
+// ============================================================================
+
+/// Outcome of comparing a variant against the baseline
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// Significantly faster than the baseline
+    Faster,
+    /// Significantly slower than the baseline
+    Slower,
+    /// No statistically significant difference
+    Unchanged,
+    /// Variant not present in the baseline
+    New,
+    /// Baseline measured in a unit that cannot be converted (see `unit_scale`)
+    Incomparable,
+}
+
+impl Verdict {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Verdict::Faster => "faster",
+            Verdict::Slower => "SLOWER",
+            Verdict::Unchanged => "~",
+            Verdict::New => "new",
+            Verdict::Incomparable => "n/a",
+        }
+    }
+}
+
+/// Per-variant delta between a baseline and the current run
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub algorithm: String,
+    pub input_size: usize,
+    pub params: Params,
+    pub variant: String,
+    /// Baseline average (None if the variant is new)
+    pub baseline_avg: Option<f64>,
+    pub current_avg: f64,
+    /// Relative change in percent (negative = faster)
+    pub delta_pct: Option<f64>,
+    /// Welch's t-test two-sided p-value
+    pub p_value: Option<f64>,
+    pub verdict: Verdict,
+}
+
+fn summary(r: &VariantResult, scale: f64) -> Summary {
+    Summary {
+        mean: r.avg_nanos_f64 * scale,
+        std_dev: r.std_dev_f64 * scale,
+        n: r.iterations,
+    }
+}
+
+/// Factor converting baseline values into the unit of the current run: 1 for
+/// the same unit, otherwise through nanoseconds with the calibrated counter
+/// frequency (`None` if a cycle report has none)
+pub fn unit_scale(baseline: &RunReport, current: &RunReport) -> Option<f64> {
+    if baseline.unit == current.unit {
+        return Some(1.0);
+    }
+    Some(baseline.nanos_per_unit()? / current.nanos_per_unit()?)
+}
+
+/// Compare every entry of `current` with the matching entry in `baseline`.
+///
+/// A difference is reported as significant when the p-value is below `alpha`.
+/// Baselines measured in another unit are converted with `unit_scale`; when
+/// that is not possible, matching variants are `Verdict::Incomparable`.
+pub fn compare(baseline: &RunReport, current: &RunReport, alpha: f64) -> Vec<Comparison> {
+    let scale = unit_scale(baseline, current);
+    current
+        .entries
+        .iter()
+        .map(|entry| {
+            let base = baseline.find(&entry.algorithm, entry.input_size, &entry.params, &entry.result.name);
+            let current_avg = entry.result.avg_nanos_f64;
+
+            let (baseline_avg, delta_pct, p_value, verdict) = match base {
+                None => (None, None, None, Verdict::New),
+                Some(_) if scale.is_none() => (None, None, None, Verdict::Incomparable),
+                Some(base) => {
+                    let scale = scale.unwrap_or(1.0);
+                    let base_avg = base.result.avg_nanos_f64 * scale;
+                    let delta = if base_avg > 0.0 {
+                        Some((current_avg - base_avg) / base_avg * 100.0)
+                    } else {
+                        None
+                    };
+                    let p = welch_t_test(summary(&base.result, scale), summary(&entry.result, 1.0));
+                    let verdict = match p {
+                        Some(p) if p < alpha && current_avg < base_avg => Verdict::Faster,
+                        Some(p) if p < alpha && current_avg > base_avg => Verdict::Slower,
+                        _ => Verdict::Unchanged,
+                    };
+                    (Some(base_avg), delta, p, verdict)
+                }
+            };
+
+            Comparison {
+                algorithm: entry.algorithm.clone(),
+                input_size: entry.input_size,
+                params: entry.params.clone(),
+                variant: entry.result.name.clone(),
+                baseline_avg,
+                current_avg,
+                delta_pct,
+                p_value,
+                verdict,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(name: &str, avg: f64, std_dev: u64) -> VariantResult {
+        VariantResult {
+            name: name.to_string(),
+            description: format!("{} variant", name),
+            avg_time: Duration::from_nanos(avg as u64),
+            avg_nanos_f64: avg,
+            median_time: Duration::from_nanos(avg as u64),
+            min_time: Duration::from_nanos(avg as u64 - std_dev),
+            max_time: Duration::from_nanos(avg as u64 + std_dev),
+            std_dev: Duration::from_nanos(std_dev),
+            std_dev_f64: std_dev as f64,
+            iterations: 30,
+            result_sample: Some(1.5),
+            counters: None,
+            mean_ci: Some((avg - 1.0, avg + 1.0)),
+            median_ci: None,
+            speedup: Some(SpeedupEstimate { speedup: 1.0, ci: Some((0.9, 1.1)), p_value: None }),
+            samples: Vec::new(),
+            below_noise_floor: false,
+            batch_size: 1,
+            tags: VariantTags::default(),
+            per_core: Vec::new(),
+        }
+    }
+
+    fn report(entries: Vec<(&str, usize, VariantResult)>) -> RunReport {
+        RunReport::new(
+            42,
+            &TimingConfig::default(),
+            false,
+            entries
+                .into_iter()
+                .map(|(algorithm, input_size, result)| ReportEntry {
+                    algorithm: algorithm.to_string(),
+                    input_size,
+                    params: Params::default(),
+                    work: Vec::new(),
+                    result,
+                    relative_error: Some(0.0),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_report_json_roundtrip() {
+        let mut original = report(vec![("dot_product", 64, result("original", 120.5, 4))]);
+        original.timer_overhead = Some(TimerOverhead { median: 38, noise_floor: 4, samples: 10_000 });
+        original.entries[0].result.tags = VariantTags::c().arch("x86_64");
+        original.entries[0].params = Params::new(vec![("offset".to_string(), "4".to_string())]);
+        original.entries[0].work = vec![Work::bytes(512), Work::elements(64)];
+        original.timing.clock = ClockKind::Rdtscp;
+        original.timing.cores = vec![2, 3];
+        original.entries[0].result.per_core = vec![CoreMean { core: 2, mean: 118.0, samples: 2 }];
+        let parsed = RunReport::from_json(&JsonValue::parse(&original.to_json().to_pretty_string()).unwrap()).unwrap();
+
+        assert_eq!(parsed.seed, 42);
+        assert_eq!(parsed.machine, original.machine);
+        assert_eq!(parsed.build, original.build);
+        assert_eq!(parsed.timer_overhead, original.timer_overhead);
+        assert_eq!(parsed.counter_frequency_hz, original.counter_frequency_hz);
+        assert_eq!(parsed.timing.runs_per_variant, 30);
+        assert_eq!(parsed.timing.pin_strategy, PinStrategy::PerExecution);
+        assert_eq!(parsed.timing.clock, ClockKind::Rdtscp);
+        assert_eq!(parsed.timing.cores, vec![2, 3]);
+        assert_eq!(parsed.entries.len(), 1);
+        let r = &parsed.entries[0].result;
+        assert_eq!(r.name, "original");
+        assert_eq!(r.avg_nanos_f64, 120.5);
+        assert_eq!(r.std_dev, Duration::from_nanos(4));
+        assert_eq!(r.result_sample, Some(1.5));
+        assert_eq!(r.mean_ci, Some((119.5, 121.5)));
+        assert_eq!(r.median_ci, None);
+        assert_eq!(r.speedup.as_ref().unwrap().ci, Some((0.9, 1.1)));
+        assert_eq!(r.tags.lang, Language::C);
+        assert_eq!(parsed.entries[0].params, original.entries[0].params);
+        assert_eq!(parsed.entries[0].work, original.entries[0].work);
+        assert_eq!(r.per_core, original.entries[0].result.per_core);
+    }
+
+    #[test]
+    fn test_compare_verdicts() {
+        let baseline = report(vec![
+            ("dot_product", 64, result("original", 100.0, 2)),
+            ("dot_product", 64, result("x86_64-sse2", 50.0, 2)),
+        ]);
+        let current = report(vec![
+            ("dot_product", 64, result("original", 100.2, 2)),
+            ("dot_product", 64, result("x86_64-sse2", 40.0, 2)),
+            ("dot_product", 64, result("x86_64-avx2", 30.0, 2)),
+        ]);
+
+        let cmp = compare(&baseline, &current, DEFAULT_ALPHA);
+        assert_eq!(cmp[0].verdict, Verdict::Unchanged);
+        assert_eq!(cmp[1].verdict, Verdict::Faster);
+        assert!((cmp[1].delta_pct.unwrap() + 20.0).abs() < 1e-9);
+        assert_eq!(cmp[2].verdict, Verdict::New);
+    }
+
+    #[test]
+    fn test_compare_across_units() {
+        // 100 ns measured with wall-clock time, 300 cycles at 3 GHz
+        let mut baseline = report(vec![("dot_product", 64, result("original", 100.0, 2))]);
+        baseline.unit = "ns".to_string();
+        baseline.counter_frequency_hz = None;
+        let mut current = report(vec![("dot_product", 64, result("original", 300.0, 6))]);
+        current.unit = "cycles".to_string();
+        current.counter_frequency_hz = Some(3e9);
+
+        assert_eq!(unit_scale(&baseline, &current), Some(3.0));
+        let cmp = compare(&baseline, &current, DEFAULT_ALPHA);
+        assert_eq!(cmp[0].baseline_avg, Some(300.0));
+        assert_eq!(cmp[0].verdict, Verdict::Unchanged);
+
+        current.counter_frequency_hz = None;
+        assert_eq!(unit_scale(&baseline, &current), None);
+        let cmp = compare(&baseline, &current, DEFAULT_ALPHA);
+        assert_eq!(cmp[0].verdict, Verdict::Incomparable);
+        assert_eq!((cmp[0].baseline_avg, cmp[0].delta_pct, cmp[0].p_value), (None, None, None));
+    }
+}
@@ -1,20 +1,44 @@
 //! Benchmark runner: execution engine and data structures.
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crate::registry::{AlgorithmRunner, BenchmarkResult};
-use crate::utils::bench::{shuffle, time_seed, to_nanos, Measurement};
-#[cfg(all(feature = "cpu_cycles", not(feature = "use_time")))]
-use crate::utils::cpu_affinity::CpuPinGuard;
-use crate::utils::timer::{PinStrategy, TimingConfig};
-use crate::utils::tui::{print_algo_info_box, print_results_table, sort_variants};
+use crate::registry::{AlgorithmRunner, BenchmarkResult, SizeSpace, SIZE_INDEPENDENT};
+use crate::utils::bench::{
+    calibrate_overhead, counter_frequency, derive_seed, measures_cycles, nanos_per_unit, set_batch_size, shuffle,
+    subtract_overhead, take_batch_used, time_seed, Measurement, TimerOverhead, CALIBRATION_SAMPLES,
+};
+use crate::utils::cache::{CachePreparer, InputRegion};
+use crate::utils::clock;
+use crate::utils::cpu_affinity::{
+    benchmark_cores, check_cores, core_classes, format_cpu_list, get_current_cpu, isolated_cores, CpuPinGuard,
+};
+use crate::utils::params::{combinations, Params};
+use crate::utils::perf::{self, CounterSample, CounterStats};
+use crate::utils::preflight::Preflight;
+use crate::utils::report::{compare, relative_error, ReportEntry, RunReport, DEFAULT_ALPHA};
+use crate::utils::stats::relative_ci_half_width;
+use crate::utils::filter::VariantFilter;
+use crate::utils::tags::{Language, VariantTags};
+use crate::utils::throughput::{distinct_units, Work, WorkUnit};
+use crate::utils::timer::{annotate_speedups, Batching, CoreMean, PinStrategy, TimingConfig};
+use crate::utils::tui::{
+    print_algo_info_box, print_class_table, print_preflight, print_comparison_table, print_core_table, print_counters_table,
+    print_results_table, print_throughput_by_size, sort_variants,
+};
 
 /// Raw timing data for a single variant (used for CSV export)
 pub struct RawTimingData {
     pub algo_name: String,
     pub variant_name: String,
+    pub tags: VariantTags,
     pub input_size: usize,
-    pub avg_nanos: u64,
+    pub params: Params,
+    /// Average in counter ticks (`None` when measuring wall-clock time)
+    pub avg_cycles: Option<f64>,
+    /// Average in nanoseconds (`None` if the counter frequency is unknown)
+    pub avg_nanos: Option<f64>,
+    /// Work per measurement unit, for each unit declared by the algorithm
+    pub throughput: Vec<(WorkUnit, f64)>,
     pub result_sample: Option<f64>,
 }
 
@@ -23,26 +47,43 @@ pub fn export_csv(path: &str, data: &[RawTimingData]) -> std::io::Result<()> {
     use std::io::Write;
 
     let mut file = std::fs::File::create(path)?;
+    let param_names = param_columns(data.iter().map(|d| &d.params));
+    let units = distinct_units(data.iter().flat_map(|d| d.throughput.iter().map(|(unit, _)| *unit)));
+    let throughput_header: String = units.iter().map(|u| format!("{},", u.rate_column())).collect();
 
-    writeln!(file, "algorithm,variant,compiler,input_size,avg_time_ns,result")?;
+    writeln!(
+        file,
+        "algorithm,variant,compiler,input_size,{}avg_cycles,avg_time_ns,{}result",
+        param_header(&param_names),
+        throughput_header
+    )?;
 
     for entry in data {
-        let compiler = crate::utils::C_COMPILER_NAME.unwrap_or(
-            if entry.variant_name.starts_with("c-") {
-                "Unknown"
-            } else {
-                ""
-            },
-        );
+        let compiler = if entry.tags.lang == Language::C {
+            crate::utils::C_COMPILER_NAME.unwrap_or("Unknown")
+        } else {
+            ""
+        };
+
+        let throughput: String = units
+            .iter()
+            .map(|unit| match entry.throughput.iter().find(|(u, _)| u == unit) {
+                Some((_, rate)) => format!("{},", rate),
+                None => ",".to_string(),
+            })
+            .collect();
 
         writeln!(
             file,
-            "{},{},{},{},{},{}",
+            "{},{},{},{},{}{},{},{}{}",
             entry.algo_name,
             entry.variant_name,
             compiler,
             entry.input_size,
-            entry.avg_nanos,
+            param_fields(&param_names, &entry.params),
+            entry.avg_cycles.map(|v| format!("{:.2}", v)).unwrap_or_default(),
+            entry.avg_nanos.map(|v| format!("{:.2}", v)).unwrap_or_default(),
+            throughput,
             entry.result_sample.map(|v| v.to_string()).unwrap_or_default()
         )?;
     }
@@ -50,51 +91,397 @@ pub fn export_csv(path: &str, data: &[RawTimingData]) -> std::io::Result<()> {
     Ok(())
 }
 
+/// Names of all parameters in `params`, in order of first appearance (one CSV column each)
+fn param_columns<'a>(params: impl Iterator<Item = &'a Params>) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for params in params {
+        for (name, _) in params.iter() {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Header cells of the parameter columns, each followed by a comma
+fn param_header(names: &[String]) -> String {
+    names.iter().map(|n| format!("{},", n)).collect()
+}
+
+/// Parameter values of one row, each followed by a comma (empty if not a parameter of the algorithm)
+fn param_fields(names: &[String], params: &Params) -> String {
+    names
+        .iter()
+        .map(|n| format!("{},", params.get(n).unwrap_or_default()))
+        .collect()
+}
+
+/// A single measurement in schedule order (used for raw CSV export)
+pub struct RawSample {
+    /// Position of the task in the randomized schedule
+    pub task_index: usize,
+    pub algo_name: String,
+    pub variant_name: String,
+    pub input_size: usize,
+    pub params: Params,
+    /// Index of this run among the runs of the same variant (chronological)
+    pub run_index: usize,
+    /// Core the task ran on (pinned core, or current core when not pinned)
+    pub core: Option<usize>,
+    /// Nanoseconds since the start of the measurement phase, taken after the task
+    pub timestamp_ns: u64,
+    /// Measured value (cycles or nanoseconds, see `bench::unit_name`)
+    pub value: u64,
+    /// Iterations timed together in `value` (see `measure_batched!`)
+    pub batch_size: usize,
+}
+
+/// Export every individual measurement to a CSV file, in schedule order
+pub fn export_raw_csv(path: &str, samples: &[RawSample]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let unit = crate::utils::bench::unit_name();
+    let param_names = param_columns(samples.iter().map(|s| &s.params));
+    let nanos_per_unit = nanos_per_unit();
+
+    writeln!(
+        file,
+        "task,algorithm,variant,input_size,{}run,core,timestamp_ns,value,value_ns,batch_size,unit",
+        param_header(&param_names)
+    )?;
+
+    for sample in samples {
+        writeln!(
+            file,
+            "{},{},{},{},{}{},{},{},{},{},{},{}",
+            sample.task_index,
+            sample.algo_name,
+            sample.variant_name,
+            sample.input_size,
+            param_fields(&param_names, &sample.params),
+            sample.run_index,
+            sample.core.map(|c| c.to_string()).unwrap_or_default(),
+            sample.timestamp_ns,
+            sample.value,
+            nanos_per_unit.map(|ns| format!("{:.1}", sample.value as f64 * ns)).unwrap_or_default(),
+            sample.batch_size,
+            unit
+        )?;
+    }
+
+    file.flush()
+}
+
+/// Input sizes of algorithms without their own defaults (`SizeSpace::Any`)
+pub const DEFAULT_SIZES: &[usize] = &[64, 256, 1024, 4096, 16384];
+
+/// Options for a benchmark session
+#[derive(Clone, Debug, Default)]
+pub struct BenchmarkOptions {
+    /// Input sizes for every size-dependent algorithm (default: each algorithm's
+    /// `AlgorithmRunner::size_space`)
+    pub input_sizes: Option<Vec<usize>>,
+    /// Per-algorithm input sizes, taking precedence over `input_sizes`
+    pub algorithm_sizes: Vec<(String, Vec<usize>)>,
+    /// Values to benchmark per parameter name (`--param`; default: all declared values)
+    pub param_values: Vec<(String, Vec<String>)>,
+    /// Random seed for the task schedule (default: time-based)
+    pub seed: Option<u64>,
+    /// Export aggregated timings to this CSV file
+    pub csv_path: Option<String>,
+    /// Export every individual measurement to this CSV file
+    pub raw_csv_path: Option<String>,
+    /// Export full statistics with machine and build metadata to this JSON file
+    pub json_path: Option<String>,
+    /// Trim 1% extremes from measurements before computing statistics
+    pub filter_outliers: bool,
+    /// Save the full run as a baseline snapshot (same format as `json_path`)
+    pub save_path: Option<String>,
+    /// Compare the run against a snapshot previously saved with `save_path`
+    pub compare_path: Option<String>,
+    /// Only benchmark the selected variants (`--variants`, `--exclude` and tag filters)
+    pub variant_filter: VariantFilter,
+    /// Refuse to benchmark when the preflight check warns (`--strict`, see `preflight`)
+    pub strict: bool,
+    /// Run the schedule once per core class of a hybrid CPU (`--core-classes`)
+    pub core_classes: bool,
+    /// Runs, warmup, pinning and counter configuration
+    pub timing: TimingConfig,
+}
+
+impl BenchmarkOptions {
+    /// Input sizes to benchmark for an algorithm: the per-algorithm sizes, then
+    /// `input_sizes`, then the algorithm's defaults (a single
+    /// `SIZE_INDEPENDENT` for size-independent algorithms)
+    pub fn sizes_for(&self, algorithm: &dyn AlgorithmRunner) -> Vec<usize> {
+        let space = algorithm.size_space();
+        if space == SizeSpace::Independent {
+            return vec![SIZE_INDEPENDENT];
+        }
+        if let Some((_, sizes)) = self.algorithm_sizes.iter().find(|(name, _)| name == algorithm.name()) {
+            return sizes.clone();
+        }
+        match (&self.input_sizes, space) {
+            (Some(sizes), _) => sizes.clone(),
+            (None, SizeSpace::Defaults(sizes)) => sizes.to_vec(),
+            (None, _) => DEFAULT_SIZES.to_vec(),
+        }
+    }
+
+    /// Every combination of input size and parameter values to benchmark for an
+    /// algorithm, sizes varying fastest
+    pub fn points_for(&self, algorithm: &dyn AlgorithmRunner) -> Vec<BenchPoint> {
+        let sizes = self.sizes_for(algorithm);
+        combinations(&algorithm.parameters(), &self.param_values)
+            .into_iter()
+            .flat_map(|params| {
+                sizes.iter().map(move |&size| BenchPoint {
+                    size,
+                    work: algorithm.work(size, &params),
+                    params: params.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// One benchmarked configuration of an algorithm: input size and parameter values
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BenchPoint {
+    pub size: usize,
+    pub params: Params,
+    /// Work done by one call (`AlgorithmRunner::work`)
+    pub work: Vec<Work>,
+}
+
+impl BenchPoint {
+    /// Table heading (e.g. "Size: 64, pattern=sorted"), without the size
+    /// of size-independent algorithms
+    pub fn label(&self) -> String {
+        match (self.size, self.params.is_empty()) {
+            (SIZE_INDEPENDENT, _) => self.params.to_string(),
+            (size, true) => format!("Size: {}", size),
+            (size, false) => format!("Size: {}, {}", size, self.params),
+        }
+    }
+}
+
 /// Run benchmarks for one or more algorithms with global randomization.
 ///
 /// This is the unified entry point for all benchmarking. It:
-/// 1. Collects ALL closures from ALL algorithms for ALL sizes into a flat Vec
+/// 1. Collects ALL closures from ALL algorithms for ALL sizes and parameter
+///    values into a flat Vec (input data derived from the seed, see `bench::derive_seed`)
 /// 2. Generates tasks (closure_idx, run_idx) and shuffles globally
-/// 3. Executes with CPU pinning
+/// 3. Executes with CPU pinning (in adaptive mode, schedules further randomized
+///    rounds for variants that are not yet precise enough)
 /// 4. Groups results and displays/exports them
-pub fn run_benchmarks(
+///
+/// Before that, it prints the preflight check (see `preflight`) for the cores
+/// it pins to. With `core_classes`, all of this is repeated once per core class
+/// (see `run_per_core_class`).
+///
+/// Fails when the clock is unavailable, a core cannot be pinned to, the
+/// baseline cannot be loaded, or `strict` is set and the preflight warns.
+pub fn run_benchmarks(algorithms: &[&dyn AlgorithmRunner], options: &BenchmarkOptions) -> Result<(), String> {
+    let effective_seed = options.seed.unwrap_or_else(time_seed);
+    if options.core_classes {
+        run_per_core_class(algorithms, options, effective_seed)
+    } else {
+        run_schedule(algorithms, options, effective_seed).map(|_| ())
+    }
+}
+
+/// Run the whole randomized schedule once per core class (P-cores, E-cores, ...),
+/// with the same seed, then compare the classes side by side.
+///
+/// Each class runs on all of its cores (restricted to `timing.cores` if set).
+/// Exports get the class name appended (`results.json` -> `results-p-cores.json`),
+/// and so does the baseline to compare with.
+fn run_per_core_class(
     algorithms: &[&dyn AlgorithmRunner],
-    input_sizes: &[usize],
-    runs: usize,
-    seed: Option<u64>,
-    csv_path: Option<&str>,
-    filter_outliers: bool,
-    pin_strategy: PinStrategy,
-) {
-    let effective_seed = seed.unwrap_or_else(time_seed);
-    let config = TimingConfig {
-        runs_per_variant: runs,
-        warmup_iterations: 10,
-        pin_strategy,
-    };
+    options: &BenchmarkOptions,
+    effective_seed: u64,
+) -> Result<(), String> {
+    let classes = core_classes(&options.timing.cores);
+    if classes.len() < 2 {
+        match classes.first() {
+            Some(class) => println!("  Core classes: all cores are of the same type ({})", class.detail),
+            None => println!("  Core classes: core types not detected (no cpu_capacity, core_type or cpufreq in sysfs)"),
+        }
+        println!("  Running a single schedule.");
+        println!();
+        return run_schedule(algorithms, options, effective_seed).map(|_| ());
+    }
 
-    print_config_info(seed, effective_seed, filter_outliers, &config);
+    println!("  Core classes:");
+    for class in &classes {
+        println!("    {:<10} cpu{} ({})", class.name, format_cpu_list(&class.cores), class.detail);
+    }
+    println!();
 
-    let mut closures = collect_closures(algorithms, input_sizes);
+    let mut runs = Vec::new();
+    for class in &classes {
+        println!("=== {} (cpu{}) ===", class.name, format_cpu_list(&class.cores));
+        println!();
+        let slug = class.name.to_lowercase().replace(' ', "-");
+        let for_class = |path: &Option<String>| path.as_deref().map(|p| class_path(p, &slug));
+        let class_options = BenchmarkOptions {
+            csv_path: for_class(&options.csv_path),
+            raw_csv_path: for_class(&options.raw_csv_path),
+            json_path: for_class(&options.json_path),
+            save_path: for_class(&options.save_path),
+            compare_path: for_class(&options.compare_path),
+            timing: TimingConfig { cores: class.cores.clone(), ..options.timing.clone() },
+            ..options.clone()
+        };
+        match run_schedule(algorithms, &class_options, effective_seed)? {
+            Some(grouped) => runs.push(grouped),
+            None => return Ok(()),
+        }
+    }
+
+    let names: Vec<&str> = classes.iter().map(|c| c.name.as_str()).collect();
+    let points: Vec<Vec<BenchPoint>> = algorithms.iter().map(|a| options.points_for(*a)).collect();
+    println!("=== Core classes side by side ===");
+    println!();
+    for (algo_idx, algo) in algorithms.iter().enumerate() {
+        let points_with_results = runs[0][algo_idx].iter().filter(|r| !r.is_empty()).count();
+        if points_with_results == 0 {
+            continue;
+        }
+        println!("  {}", algo.name());
+        for (point_idx, point) in points[algo_idx].iter().enumerate() {
+            let per_class: Vec<&[BenchmarkResult]> = runs.iter().map(|run| run[algo_idx][point_idx].as_slice()).collect();
+            if !per_class[0].is_empty() {
+                let label = (points_with_results > 1).then(|| point.label());
+                print_class_table(&names, &per_class, label.as_deref());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `path` with `-suffix` before its extension ("out.json" -> "out-p-cores.json")
+fn class_path(path: &str, suffix: &str) -> String {
+    let p = std::path::Path::new(path);
+    match (p.file_stem(), p.extension()) {
+        (Some(stem), Some(ext)) => p
+            .with_file_name(format!("{}-{}.{}", stem.to_string_lossy(), suffix, ext.to_string_lossy()))
+            .to_string_lossy()
+            .into_owned(),
+        _ => format!("{}-{}", path, suffix),
+    }
+}
+
+/// One run of the randomized schedule; returns the grouped results (`None` if
+/// no variant was selected)
+fn run_schedule(
+    algorithms: &[&dyn AlgorithmRunner],
+    options: &BenchmarkOptions,
+    effective_seed: u64,
+) -> Result<Option<Vec<Vec<Vec<BenchmarkResult>>>>, String> {
+    let config = &options.timing;
+    let points: Vec<Vec<BenchPoint>> = algorithms.iter().map(|a| options.points_for(*a)).collect();
+    let points = &points;
+
+    // The cores must be pinnable: falling back to an arbitrary core would
+    // mislabel the results
+    let cores = benchmark_cores(&config.cores);
+    clock::select(config.clock)?;
+    check_cores(&cores)?;
+    if let (false, Batching::Fixed(n)) = (config.cache.is_hot(), config.batching) {
+        return Err(format!(
+            "--batch {} cannot be combined with --cache {}: only the first call of a batch would see cold caches",
+            n,
+            config.cache.as_str()
+        ));
+    }
+
+    // Checked on the cores the schedule pins to (the current one if none is selected)
+    let preflight_cores = if cores.is_empty() { get_current_cpu().into_iter().collect() } else { cores.clone() };
+    let preflight = Preflight::run(&preflight_cores);
+    print_preflight(&preflight);
+    if options.strict && preflight.has_warnings() {
+        let names: Vec<&str> = preflight.warnings().iter().map(|c| c.name).collect();
+        return Err(format!("--strict: refusing to benchmark ({})", names.join(", ")));
+    }
+    print_config_info(options.seed, effective_seed, options.filter_outliers, config);
+    if !options.variant_filter.is_empty() {
+        println!("  Variant filter: {}", options.variant_filter.describe());
+    }
+
+    let overhead = calibrate_overhead(CALIBRATION_SAMPLES, cores.first().copied());
+    print_overhead_info(&overhead, config.subtract_overhead);
+    print_frequency_info();
+
+    let baseline = match options.compare_path.as_deref().map(RunReport::load) {
+        Some(Ok(report)) => Some(report),
+        Some(Err(e)) => return Err(format!("Failed to load baseline: {}", e)),
+        None => None,
+    };
+
+    let mut closures = collect_closures(algorithms, points, effective_seed, &options.variant_filter);
     if closures.is_empty() {
-        println!("  No variants to benchmark.");
-        return;
+        if options.variant_filter.is_empty() {
+            println!("  No variants to benchmark.");
+        } else {
+            println!("  No variants match the filter ({}).", options.variant_filter.describe());
+        }
+        return Ok(None);
     }
 
     warmup_closures(&mut closures, config.warmup_iterations);
+    // Only the first iteration of a batch would see cold caches
+    let batching = if config.cache.is_hot() { config.batching } else { Batching::Off };
+    choose_batch_sizes(&mut closures, batching, &overhead);
+    if !config.cache.is_hot() {
+        print_unbatched_note(&closures, algorithms, config);
+    }
     let tasks = generate_shuffled_tasks(closures.len(), config.runs_per_variant, effective_seed);
-    let (measurements, result_samples) = execute_tasks(&mut closures, tasks, &config);
+    let samples = execute_tasks(&mut closures, tasks, config, effective_seed);
+
+    if let Some(path) = &options.raw_csv_path {
+        let raw_samples = collect_raw_samples(&closures, &samples, algorithms, points);
+        match export_raw_csv(path, &raw_samples) {
+            Ok(()) => println!("  Raw samples ({}) exported to: {}", raw_samples.len(), path),
+            Err(e) => eprintln!("  Warning: Failed to export raw samples: {}", e),
+        }
+        println!();
+    }
 
     let (grouped, raw_data) = group_results(
-        closures, measurements, result_samples, algorithms, 
-        input_sizes.len(), config.runs_per_variant, filter_outliers
+        closures, samples, algorithms,
+        points, options.filter_outliers,
+        &overhead, config
     );
 
-    if let Some(path) = csv_path {
+    if let Some(path) = &options.csv_path {
         export_csv_with_message(path, &raw_data);
     }
 
-    display_results(algorithms, input_sizes, &grouped, config.runs_per_variant, filter_outliers);
+    display_results(algorithms, points, &grouped, options.filter_outliers);
+
+    if options.json_path.is_some() || options.save_path.is_some() || baseline.is_some() {
+        let report = RunReport {
+            timer_overhead: Some(overhead),
+            ..build_report(algorithms, points, &grouped, effective_seed, options)
+        };
+
+        if let Some(path) = &options.json_path {
+            save_report_with_message(&report, path, "Results exported to");
+        }
+        if let Some(path) = &options.save_path {
+            save_report_with_message(&report, path, "Snapshot saved to");
+        }
+
+        if let (Some(baseline), Some(path)) = (&baseline, &options.compare_path) {
+            let comparisons = compare(baseline, &report, DEFAULT_ALPHA);
+            print_comparison_table(path, baseline, &report, &comparisons);
+        }
+    }
+    Ok(Some(grouped))
 }
 
 // ============================================================================
@@ -106,26 +493,96 @@ fn print_config_info(seed: Option<u64>, effective_seed: u64, filter_outliers: bo
     if filter_outliers {
         println!("  Outlier filtering: enabled (trimming 1% extremes)");
     }
+    println!("  Clock: {} ({})", config.clock.as_str(), config.clock.unit());
     println!("  Pin strategy: {:?}", config.pin_strategy);
+    match (benchmark_cores(&config.cores).as_slice(), config.cores.is_empty()) {
+        ([], _) if !isolated_cores().is_empty() => println!(
+            "  Cores: current core (isolated cores cpu{} cannot be pinned to, e.g. outside the cpuset)",
+            format_cpu_list(&isolated_cores())
+        ),
+        ([], _) => println!("  Cores: current core (no isolated cores)"),
+        ([core], true) => println!("  Cores: cpu{} (isolated)", core),
+        (cores, _) => println!(
+            "  Cores: {}{}",
+            cores.iter().map(|c| format!("cpu{}", c)).collect::<Vec<_>>().join(", "),
+            if cores.len() > 1 { " (runs of each variant rotate through them)" } else { "" }
+        ),
+    }
+    if let Some(steps) = CachePreparer::new(config.cache).describe() {
+        println!("  Cache: {} ({} before each sample)", config.cache.as_str(), steps);
+    }
+    if config.hw_counters {
+        println!("  Hardware counters: requested (perf_event_open)");
+    }
+    if config.is_adaptive() {
+        let target = config
+            .target_precision
+            .map(|p| format!("±{}% (95% CI of the mean) or ", p * 100.0))
+            .unwrap_or_default();
+        println!(
+            "  Adaptive sampling: until {}{:.1}s per variant, {}-{} runs",
+            target,
+            config.effective_time_budget().as_secs_f64(),
+            config.runs_per_variant,
+            config.max_runs_per_variant
+        );
+    }
+}
+
+fn print_overhead_info(overhead: &TimerOverhead, subtracted: bool) {
+    let unit = crate::utils::bench::unit_name();
+    println!(
+        "  Timer overhead: {} {} per measurement (noise floor ±{} {}){}",
+        overhead.median,
+        unit,
+        overhead.noise_floor,
+        unit,
+        if subtracted { ", subtracted from samples" } else { "" }
+    );
+}
+
+/// Calibrate the timestamp counter, for the nanosecond columns (`rdtsc` and `rdtscp` clocks only)
+fn print_frequency_info() {
+    if !clock::selected().reads_tsc() {
+        return;
+    }
+    match counter_frequency() {
+        Some(hz) => println!("  Counter frequency: {:.3} GHz (calibrated against CLOCK_MONOTONIC_RAW)", hz / 1e9),
+        None => println!("  Counter frequency: unknown (no nanosecond conversion)"),
+    }
 }
 
 fn collect_closures<'a>(
     algorithms: &[&'a dyn AlgorithmRunner],
-    input_sizes: &[usize],
+    points: &[Vec<BenchPoint>],
+    seed: u64,
+    filter: &VariantFilter,
 ) -> ClosureVec<'a> {
     println!("  Collecting benchmark closures...");
     let mut closures = Vec::new();
 
     for (algo_idx, algo) in algorithms.iter().enumerate() {
-        for (size_idx, &input_size) in input_sizes.iter().enumerate() {
-            for variant in algo.get_variant_closures(input_size) {
+        // Skip input generation entirely when no variant name can match
+        if !algo.available_variants().iter().any(|name| filter.matches_name(algo.name(), name)) {
+            continue;
+        }
+        for (point_idx, point) in points[algo_idx].iter().enumerate() {
+            // Parameter values share the inputs' seed, so they are compared on the same data
+            let input_seed = derive_seed(seed, algo.name(), point.size);
+            for variant in algo.get_variant_closures(point.size, &point.params, input_seed) {
+                if !filter.matches(algo.name(), variant.name, &variant.tags) {
+                    continue;
+                }
                 closures.push((
                     ClosureContext {
                         algo_idx,
-                        size_idx,
-                        input_size,
+                        point_idx,
                         name: variant.name,
                         description: variant.description,
+                        tags: variant.tags,
+                        inputs: variant.inputs,
+                        batch_size: 1,
+                        batchable: false,
                     },
                     variant.run,
                 ));
@@ -145,6 +602,99 @@ fn warmup_closures(closures: &mut ClosureVec, iterations: usize) {
     }
 }
 
+/// Largest batch size tried when the target cannot be reached
+const MAX_BATCH_SIZE: usize = 1 << 20;
+
+/// A batched sample should take at least this many times the timer overhead
+const BATCH_TARGET_OVERHEAD_RATIO: u64 = 100;
+
+/// Cold cache modes time one call per sample: name the algorithms whose
+/// variants are normally batched, since their results are then dominated by
+/// the timer overhead (they are marked as within the noise floor)
+fn print_unbatched_note(closures: &ClosureVec, algorithms: &[&dyn AlgorithmRunner], config: &TimingConfig) {
+    let mut names: Vec<&str> = closures
+        .iter()
+        .filter(|(ctx, _)| ctx.batchable)
+        .map(|(ctx, _)| algorithms[ctx.algo_idx].name())
+        .collect();
+    names.dedup();
+    if !names.is_empty() {
+        println!(
+            "  Batching: off with --cache {}; {} time a single call per sample, mostly timer overhead (marked †)",
+            config.cache.as_str(),
+            names.join(", ")
+        );
+    }
+}
+
+/// Choose the iterations per sample of closures timed with `measure_batched!`.
+///
+/// All variants of one algorithm, size and parameter values share a batch size, so their samples stay
+/// comparable, and they run the same calls while it is chosen (stateful variants
+/// such as PRNGs stay in sync). In `Auto` mode, the batch size doubles until the
+/// fastest variant of the group takes `BATCH_TARGET_OVERHEAD_RATIO` times the timer overhead.
+fn choose_batch_sizes(closures: &mut ClosureVec, batching: Batching, overhead: &TimerOverhead) {
+    // Closures that honor the batch size (detected from a single call)
+    let mut batched: Vec<usize> = Vec::new();
+    for (idx, (ctx, closure)) in closures.iter_mut().enumerate() {
+        set_batch_size(1);
+        take_batch_used();
+        let _ = std::hint::black_box(closure());
+        ctx.batchable = take_batch_used();
+        if ctx.batchable {
+            batched.push(idx);
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for idx in batched {
+        let key = (closures[idx].0.algo_idx, closures[idx].0.point_idx);
+        match groups.iter_mut().find(|g| (closures[g[0]].0.algo_idx, closures[g[0]].0.point_idx) == key) {
+            Some(group) => group.push(idx),
+            None => groups.push(vec![idx]),
+        }
+    }
+
+    let target = (overhead.median * BATCH_TARGET_OVERHEAD_RATIO).max(1000);
+    for group in &groups {
+        let batch_size = match batching {
+            Batching::Off => 1,
+            Batching::Fixed(n) => n,
+            Batching::Auto => {
+                let mut n = 1;
+                while n < MAX_BATCH_SIZE {
+                    set_batch_size(n);
+                    let fastest = group
+                        .iter()
+                        .map(|&idx| (closures[idx].1)().0)
+                        .min()
+                        .unwrap_or(0);
+                    if fastest >= target {
+                        break;
+                    }
+                    n *= 2;
+                }
+                n
+            }
+        };
+        for &idx in group {
+            closures[idx].0.batch_size = batch_size;
+        }
+    }
+    set_batch_size(1);
+    take_batch_used();
+
+    if !groups.is_empty() && batching != Batching::Off {
+        println!(
+            "  Batching: {} variants timed per batch ({}, ≥ {} {} per sample)",
+            groups.iter().map(|g| g.len()).sum::<usize>(),
+            batching.as_string(),
+            target,
+            crate::utils::bench::unit_name()
+        );
+    }
+}
+
 fn generate_shuffled_tasks(num_closures: usize, runs: usize, seed: u64) -> Vec<(usize, usize)> {
     let mut tasks: Vec<(usize, usize)> = (0..num_closures)
         .flat_map(|c| (0..runs).map(move |r| (c, r)))
@@ -158,53 +708,251 @@ fn execute_tasks(
     closures: &mut ClosureVec,
     tasks: Vec<(usize, usize)>,
     config: &TimingConfig,
-) -> (Vec<Vec<Measurement>>, Vec<Option<f64>>) {
-    let runs = config.runs_per_variant;
-    let mut measurements: Vec<Vec<Measurement>> = vec![Vec::with_capacity(runs); closures.len()];
-    let mut result_samples: Vec<Option<f64>> = vec![None; closures.len()];
+    seed: u64,
+) -> SampleStore {
+    let mut samples = SampleStore::new(closures.len(), config.runs_per_variant);
 
-    match config.pin_strategy {
-        PinStrategy::Global => execute_with_global_pin(closures, tasks, &mut measurements, &mut result_samples),
-        PinStrategy::PerExecution => execute_with_per_call_pin(closures, tasks, &mut measurements, &mut result_samples),
+    let counters_enabled = config.hw_counters && enable_counters();
+    let cache = CachePreparer::new(config.cache);
+
+    execute_schedule(closures, tasks, &mut samples, config, &cache);
+
+    if config.is_adaptive() {
+        let mut round = 1;
+        while let Some(tasks) = next_adaptive_round(&samples, config, seed.wrapping_add(round)) {
+            execute_schedule(closures, tasks, &mut samples, config, &cache);
+            round += 1;
+        }
+    }
+
+    if counters_enabled {
+        perf::disable();
     }
     
     println!("\r  Completed!          ");
     println!();
-    (measurements, result_samples)
+    samples
+}
+
+fn execute_schedule(
+    closures: &mut ClosureVec,
+    tasks: Vec<(usize, usize)>,
+    samples: &mut SampleStore,
+    config: &TimingConfig,
+    cache: &CachePreparer,
+) {
+    let cores = benchmark_cores(&config.cores);
+    match config.pin_strategy {
+        PinStrategy::Global => execute_with_global_pin(closures, tasks, samples, &cores, cache),
+        PinStrategy::PerExecution => execute_with_per_call_pin(closures, tasks, samples, &cores, cache),
+    }
+}
+
+/// Core of run `run_idx` of a variant: runs rotate through `cores`, so every
+/// variant gets the same number of runs on each (`None`: the current core)
+fn core_for_run(cores: &[usize], run_idx: usize) -> Option<usize> {
+    (!cores.is_empty()).then(|| cores[run_idx % cores.len()])
+}
+
+/// Schedule more runs for the variants that have neither reached the target
+/// precision nor exhausted their time budget (or run limit).
+///
+/// The number of extra runs is estimated from the current precision (the CI
+/// width shrinks with the square root of the sample count), bounded by the
+/// remaining budget and by doubling per round so the estimate gets refined.
+/// Returns `None` once every variant is done.
+fn next_adaptive_round(samples: &SampleStore, config: &TimingConfig, seed: u64) -> Option<Vec<(usize, usize)>> {
+    let budget = config.effective_time_budget();
+    let mut tasks = Vec::new();
+    let mut active_variants = 0;
+
+    for (closure_idx, measurements) in samples.measurements.iter().enumerate() {
+        let n = measurements.len();
+        let spent = samples.spent[closure_idx];
+        if n == 0 || n >= config.max_runs_per_variant || spent >= budget {
+            continue;
+        }
+
+        let values: Vec<u64> = measurements.to_vec();
+        let needed = match (config.target_precision, relative_ci_half_width(&values)) {
+            (Some(target), Some(precision)) if precision <= target => continue,
+            (Some(target), Some(precision)) => ((n as f64) * (precision / target).powi(2)).ceil() as usize,
+            _ => n * 2,
+        };
+
+        let per_run_ns = (spent.as_nanos() / n as u128).max(1);
+        let affordable = ((budget - spent).as_nanos() / per_run_ns).max(1) as usize;
+
+        let runs = needed
+            .saturating_sub(n)
+            .max(config.runs_per_variant)
+            .min(n)
+            .min(affordable)
+            .min(config.max_runs_per_variant - n);
+
+        tasks.extend((n..n + runs).map(|run| (closure_idx, run)));
+        active_variants += 1;
+    }
+
+    if tasks.is_empty() {
+        return None;
+    }
+    shuffle(&mut tasks, seed);
+    println!("\r  Adaptive round: {} tasks for {} variants...", tasks.len(), active_variants);
+    Some(tasks)
+}
+
+/// Open hardware counters for the benchmark thread, reporting what is available
+fn enable_counters() -> bool {
+    match perf::enable() {
+        Ok(counters) => {
+            let names: Vec<&str> = counters.iter().map(|c| c.name()).collect();
+            println!("  Counting: {}", names.join(", "));
+            true
+        }
+        Err(e) => {
+            eprintln!("  Warning: Hardware counters unavailable: {}", e);
+            false
+        }
+    }
+}
+
+fn collect_raw_samples(
+    closures: &ClosureVec,
+    samples: &SampleStore,
+    algorithms: &[&dyn AlgorithmRunner],
+    points: &[Vec<BenchPoint>],
+) -> Vec<RawSample> {
+    samples
+        .log
+        .iter()
+        .enumerate()
+        .map(|(task_index, record)| {
+            let (ctx, _) = &closures[record.closure_idx];
+            let point = &points[ctx.algo_idx][ctx.point_idx];
+            RawSample {
+                task_index,
+                algo_name: algorithms[ctx.algo_idx].name().to_string(),
+                variant_name: ctx.name.to_string(),
+                input_size: point.size,
+                params: point.params.clone(),
+                run_index: record.run_idx,
+                core: record.core,
+                timestamp_ns: record.timestamp_ns,
+                value: record.value,
+                batch_size: ctx.batch_size,
+            }
+        })
+        .collect()
 }
 
 fn group_results(
     closures: ClosureVec,
-    mut measurements: Vec<Vec<Measurement>>,
-    result_samples: Vec<Option<f64>>,
+    mut samples: SampleStore,
     algorithms: &[&dyn AlgorithmRunner],
-    num_sizes: usize,
-    runs: usize,
+    points: &[Vec<BenchPoint>],
     filter_outliers: bool,
+    overhead: &TimerOverhead,
+    config: &TimingConfig,
 ) -> (Vec<Vec<Vec<BenchmarkResult>>>, Vec<RawTimingData>) {
-    let num_algos = algorithms.len();
-    let mut grouped: Vec<Vec<Vec<BenchmarkResult>>> = vec![vec![Vec::new(); num_sizes]; num_algos];
+    let subtract = config.subtract_overhead;
+    // Cold cache modes turn batching off
+    let unbatched = !config.cache.is_hot();
+    let mut grouped: Vec<Vec<Vec<BenchmarkResult>>> =
+        points.iter().map(|points| vec![Vec::new(); points.len()]).collect();
     let mut raw_data: Vec<RawTimingData> = Vec::new();
+    let mut core_samples: Vec<Vec<(usize, u64)>> = vec![Vec::new(); closures.len()];
+    for record in &samples.log {
+        if let Some(core) = record.core {
+            core_samples[record.closure_idx].push((core, record.value));
+        }
+    }
 
     for (closure_idx, (ctx, _)) in closures.into_iter().enumerate() {
-        let timing_values = std::mem::take(&mut measurements[closure_idx]);
-        let result_sample = result_samples[closure_idx];
+        let mut timing_values = std::mem::take(&mut samples.measurements[closure_idx]);
+        if subtract {
+            timing_values.iter_mut().for_each(|m| *m = subtract_overhead(*m, overhead.median));
+        }
+        let result_sample = samples.result_samples[closure_idx];
 
-        let result = compute_result(&timing_values, ctx.name, ctx.description, runs, result_sample, filter_outliers);
+        let runs = timing_values.len();
+        let mut result = compute_result(&timing_values, ctx.name, ctx.description, runs, result_sample, filter_outliers);
+        result.tags = ctx.tags;
+        result.counters = CounterStats::from_samples(&samples.counters[closure_idx]);
+        // A single call of a batched closure is mostly the measurement itself
+        result.below_noise_floor =
+            overhead.is_below_noise_floor(result.avg_nanos_f64, subtract) || (unbatched && ctx.batchable);
+        result.per_core = CoreMean::from_samples(core_samples[closure_idx].iter().map(|&(core, value)| {
+            (core, if subtract { subtract_overhead(value, overhead.median) } else { value })
+        }));
+        result.scale_to_batch(ctx.batch_size);
 
+        let point = &points[ctx.algo_idx][ctx.point_idx];
         raw_data.push(RawTimingData {
             algo_name: algorithms[ctx.algo_idx].name().to_string(),
             variant_name: result.name.clone(),
-            input_size: ctx.input_size,
-            avg_nanos: result.avg_time.as_nanos() as u64,
+            tags: ctx.tags,
+            input_size: point.size,
+            params: point.params.clone(),
+            avg_cycles: measures_cycles().then_some(result.avg_nanos_f64),
+            avg_nanos: nanos_per_unit().map(|ns| result.avg_nanos_f64 * ns),
+            throughput: point
+                .work
+                .iter()
+                .filter_map(|w| w.rate(result.avg_nanos_f64).map(|rate| (w.unit, rate)))
+                .collect(),
             result_sample,
         });
 
-        grouped[ctx.algo_idx][ctx.size_idx].push(result);
+        grouped[ctx.algo_idx][ctx.point_idx].push(result);
+    }
+
+    // Baseline first, then speedups with their confidence intervals
+    for results in grouped.iter_mut().flatten() {
+        sort_variants(results);
+        annotate_speedups(results);
     }
     (grouped, raw_data)
 }
 
+fn build_report(
+    algorithms: &[&dyn AlgorithmRunner],
+    points: &[Vec<BenchPoint>],
+    grouped: &[Vec<Vec<BenchmarkResult>>],
+    seed: u64,
+    options: &BenchmarkOptions,
+) -> RunReport {
+    let mut entries = Vec::new();
+    for (algo_idx, algo) in algorithms.iter().enumerate() {
+        for (point_idx, point) in points[algo_idx].iter().enumerate() {
+            let results = &grouped[algo_idx][point_idx];
+            let reference = results.first().and_then(|r| r.result_sample);
+
+            for result in results.iter().cloned() {
+                entries.push(ReportEntry {
+                    algorithm: algo.name().to_string(),
+                    input_size: point.size,
+                    params: point.params.clone(),
+                    work: point.work.clone(),
+                    relative_error: result
+                        .result_sample
+                        .map(|_| relative_error(result.result_sample, reference)),
+                    result,
+                });
+            }
+        }
+    }
+    RunReport::new(seed, &options.timing, options.filter_outliers, entries)
+}
+
+fn save_report_with_message(report: &RunReport, path: &str, message: &str) {
+    match report.save(path) {
+        Ok(()) => println!("  {}: {}", message, path),
+        Err(e) => eprintln!("  Warning: Failed to write {}: {}", path, e),
+    }
+    println!();
+}
+
 fn export_csv_with_message(path: &str, data: &[RawTimingData]) {
     match export_csv(path, data) {
         Ok(()) => println!("  Raw data exported to: {}", path),
@@ -215,29 +963,55 @@ fn export_csv_with_message(path: &str, data: &[RawTimingData]) {
 
 fn display_results(
     algorithms: &[&dyn AlgorithmRunner],
-    input_sizes: &[usize],
+    points: &[Vec<BenchPoint>],
     grouped: &[Vec<Vec<BenchmarkResult>>],
-    runs: usize,
     filter_outliers: bool,
 ) {
     for (algo_idx, algo) in algorithms.iter().enumerate() {
+        let points_with_results = grouped[algo_idx].iter().filter(|r| !r.is_empty()).count();
+        if points_with_results == 0 {
+            // Every variant was filtered out
+            continue;
+        }
         print_algo_info_box(*algo);
+        let show_label = points_with_results > 1;
 
-        let sizes_with_results = grouped[algo_idx].iter().filter(|r| !r.is_empty()).count();
-        let show_size = sizes_with_results > 1;
-
-        for (size_idx, &input_size) in input_sizes.iter().enumerate() {
-            let mut results = grouped[algo_idx][size_idx].clone();
-            sort_variants(&mut results);
+        for (point_idx, point) in points[algo_idx].iter().enumerate() {
+            let results = &grouped[algo_idx][point_idx];
 
             if !results.is_empty() {
-                print_results_table(&results, input_size, runs, show_size, filter_outliers);
+                let label = show_label.then(|| point.label());
+                print_results_table(results, label.as_deref(), &point.work, filter_outliers);
+                print_counters_table(results);
+                print_core_table(results);
             }
         }
+
+        print_throughput_summaries(points[algo_idx].as_slice(), &grouped[algo_idx]);
     }
 }
 
-/// Compute statistics from measurements (Measurement type varies by feature)
+/// Throughput across sizes for each combination of parameter values, when the
+/// algorithm declares its work and more than one size was benchmarked
+fn print_throughput_summaries(points: &[BenchPoint], grouped: &[Vec<BenchmarkResult>]) {
+    let mut start = 0;
+    while start < points.len() {
+        // Points of the same parameter values are consecutive, sizes varying fastest
+        let end = start + points[start..].iter().take_while(|p| p.params == points[start].params).count();
+        let columns: Vec<(usize, &[Work], &[BenchmarkResult])> = (start..end)
+            .filter(|&i| !points[i].work.is_empty() && !grouped[i].is_empty())
+            .map(|i| (points[i].size, points[i].work.as_slice(), grouped[i].as_slice()))
+            .collect();
+        if columns.len() > 1 {
+            let params = &points[start].params;
+            let heading = if params.is_empty() { "Throughput".to_string() } else { format!("Throughput, {}", params) };
+            print_throughput_by_size(&heading, &columns);
+        }
+        start = end;
+    }
+}
+
+/// Compute statistics from measurements (in the unit of the selected clock)
 fn compute_result(
     values: &[Measurement],
     name: &'static str,
@@ -256,12 +1030,22 @@ fn compute_result(
             min_time: Duration::ZERO,
             max_time: Duration::ZERO,
             std_dev: Duration::ZERO,
+            std_dev_f64: 0.0,
             iterations,
             result_sample,
+            counters: None,
+            mean_ci: None,
+            median_ci: None,
+            speedup: None,
+            samples: Vec::new(),
+            below_noise_floor: false,
+            batch_size: 1,
+            tags: VariantTags::default(),
+            per_core: Vec::new(),
         };
     }
 
-    let mut nanos: Vec<u64> = values.iter().map(|m| to_nanos(*m)).collect();
+    let mut nanos: Vec<u64> = values.to_vec();
     nanos.sort();
     
     let trimmed = if filter_outliers && nanos.len() > 10 {
@@ -288,9 +1072,9 @@ fn compute_result(
         })
         .sum::<f64>()
         / (trimmed.len() - 1).max(1) as f64;
-    let std_dev_val = variance.sqrt() as u64;
+    let std_dev_f64 = variance.sqrt();
 
-    BenchmarkResult {
+    let mut result = BenchmarkResult {
         name: name.to_string(),
         description: description.to_string(),
         avg_time: Duration::from_nanos(avg_val),
@@ -298,10 +1082,22 @@ fn compute_result(
         median_time: Duration::from_nanos(median_val),
         min_time: Duration::from_nanos(min_val),
         max_time: Duration::from_nanos(max_val),
-        std_dev: Duration::from_nanos(std_dev_val),
+        std_dev: Duration::from_nanos(std_dev_f64 as u64),
+        std_dev_f64,
         iterations,
         result_sample,
-    }
+        counters: None,
+        mean_ci: None,
+        median_ci: None,
+        speedup: None,
+        samples: trimmed.to_vec(),
+        below_noise_floor: false,
+        batch_size: 1,
+        tags: VariantTags::default(),
+        per_core: Vec::new(),
+    };
+    result.compute_intervals();
+    result
 }
 
 // ============================================================================
@@ -311,97 +1107,137 @@ fn compute_result(
 /// Context for each closure during execution
 struct ClosureContext {
     algo_idx: usize,
-    size_idx: usize,
-    input_size: usize,
+    /// Index into the algorithm's `BenchPoint`s
+    point_idx: usize,
     name: &'static str,
     description: &'static str,
+    tags: VariantTags,
+    /// Memory flushed before each sample in cold-data mode
+    inputs: Vec<InputRegion>,
+    /// Iterations per sample for `measure_batched!` closures (1 otherwise)
+    batch_size: usize,
+    /// Whether the closure is timed with `measure_batched!` (set by `choose_batch_sizes`)
+    batchable: bool,
 }
 
 type ClosureVec<'a> = Vec<(ClosureContext, Box<dyn FnMut() -> (Measurement, Option<f64>) + 'a>)>;
 
-/// CPU pinned once for entire session - minimal overhead (only when cpu_cycles)
-#[cfg(all(feature = "cpu_cycles", not(feature = "use_time")))]
-fn execute_with_global_pin(
-    closures: &mut ClosureVec,
-    tasks: Vec<(usize, usize)>,
-    measurements: &mut [Vec<Measurement>],
-    result_samples: &mut [Option<f64>],
-) {
-    let _pin = CpuPinGuard::new();
-    execute_loop(closures, tasks, measurements, result_samples);
+/// Per-closure storage filled during execution
+struct SampleStore {
+    measurements: Vec<Vec<Measurement>>,
+    counters: Vec<Vec<CounterSample>>,
+    result_samples: Vec<Option<f64>>,
+    /// Wall-clock time spent per closure (including runner overhead)
+    spent: Vec<Duration>,
+    /// Every task in execution order
+    log: Vec<SampleRecord>,
+    started: Instant,
 }
 
-/// No-op when not measuring CPU cycles
-#[cfg(any(not(feature = "cpu_cycles"), feature = "use_time"))]
-fn execute_with_global_pin(
-    closures: &mut ClosureVec,
-    tasks: Vec<(usize, usize)>,
-    measurements: &mut [Vec<Measurement>],
-    result_samples: &mut [Option<f64>],
-) {
-    execute_loop(closures, tasks, measurements, result_samples);
+/// One executed task, as recorded in `SampleStore::log`
+struct SampleRecord {
+    closure_idx: usize,
+    run_idx: usize,
+    core: Option<usize>,
+    timestamp_ns: u64,
+    value: u64,
 }
 
-/// CPU pinned per call - more accurate for long benchmarks (only when cpu_cycles)
-#[cfg(all(feature = "cpu_cycles", not(feature = "use_time")))]
-fn execute_with_per_call_pin(
+impl SampleStore {
+    fn new(num_closures: usize, runs: usize) -> Self {
+        Self {
+            measurements: vec![Vec::with_capacity(runs); num_closures],
+            counters: vec![Vec::new(); num_closures],
+            result_samples: vec![None; num_closures],
+            spent: vec![Duration::ZERO; num_closures],
+            log: Vec::with_capacity(num_closures * runs),
+            started: Instant::now(),
+        }
+    }
+
+    /// Record one execution of a closure, including counters from its `measure!`
+    fn record(&mut self, closure_idx: usize, core: Option<usize>, elapsed_time: Measurement, result: Option<f64>) {
+        let timestamp_ns = self.started.elapsed().as_nanos() as u64;
+        let previous_ns = self.log.last().map(|r| r.timestamp_ns).unwrap_or(0);
+        self.spent[closure_idx] += Duration::from_nanos(timestamp_ns.saturating_sub(previous_ns));
+
+        self.log.push(SampleRecord {
+            closure_idx,
+            run_idx: self.measurements[closure_idx].len(),
+            core,
+            timestamp_ns,
+            value: elapsed_time,
+        });
+        self.measurements[closure_idx].push(elapsed_time);
+        if let Some(sample) = perf::take_last() {
+            self.counters[closure_idx].push(sample);
+        }
+        if result.is_some() {
+            self.result_samples[closure_idx] = result;
+        }
+    }
+}
+
+/// CPU pinned once for entire session - minimal overhead. With several cores,
+/// the thread is re-pinned only when consecutive tasks run on different cores.
+fn execute_with_global_pin(
     closures: &mut ClosureVec,
     tasks: Vec<(usize, usize)>,
-    measurements: &mut [Vec<Measurement>],
-    result_samples: &mut [Option<f64>],
+    samples: &mut SampleStore,
+    cores: &[usize],
+    cache: &CachePreparer,
 ) {
     let total_tasks = tasks.len();
     let report_interval = (total_tasks / 10).max(1);
+    let mut pin: Option<(Option<usize>, CpuPinGuard)> = None;
 
-    for (completed, (closure_idx, _)) in tasks.into_iter().enumerate() {
-        let (_, closure) = &mut closures[closure_idx];
-        let _pin = CpuPinGuard::new();
+    for (completed, (closure_idx, run_idx)) in tasks.into_iter().enumerate() {
+        let core = core_for_run(cores, run_idx);
+        if pin.as_ref().is_none_or(|(pinned_for, _)| *pinned_for != core) {
+            // Unpin before pinning again, so the guard restores the original affinity
+            drop(pin.take());
+            pin = Some((core, CpuPinGuard::on(core)));
+        }
+        let pinned_core = pin.as_ref().and_then(|(_, guard)| guard.core_id());
+
+        let (ctx, closure) = &mut closures[closure_idx];
+        set_batch_size(ctx.batch_size);
+        cache.prepare(&ctx.inputs);
         let (elapsed_time, result) = closure();
 
-        measurements[closure_idx].push(elapsed_time);
-        if result.is_some() {
-            result_samples[closure_idx] = result;
-        }
+        // Without a pinned core, the current core is recorded after each task
+        samples.record(closure_idx, pinned_core.or_else(get_current_cpu), elapsed_time, result);
 
         report_progress(completed, total_tasks, report_interval);
     }
 }
 
-/// No-op pinning when not measuring CPU cycles
-#[cfg(any(not(feature = "cpu_cycles"), feature = "use_time"))]
+/// CPU pinned per call - more accurate for long benchmarks
 fn execute_with_per_call_pin(
     closures: &mut ClosureVec,
     tasks: Vec<(usize, usize)>,
-    measurements: &mut [Vec<Measurement>],
-    result_samples: &mut [Option<f64>],
-) {
-    execute_loop(closures, tasks, measurements, result_samples);
-}
-
-fn execute_loop(
-    closures: &mut ClosureVec,
-    tasks: Vec<(usize, usize)>,
-    measurements: &mut [Vec<Measurement>],
-    result_samples: &mut [Option<f64>],
+    samples: &mut SampleStore,
+    cores: &[usize],
+    cache: &CachePreparer,
 ) {
     let total_tasks = tasks.len();
     let report_interval = (total_tasks / 10).max(1);
 
-    for (completed, (closure_idx, _)) in tasks.into_iter().enumerate() {
-        let (_, closure) = &mut closures[closure_idx];
+    for (completed, (closure_idx, run_idx)) in tasks.into_iter().enumerate() {
+        let (ctx, closure) = &mut closures[closure_idx];
+        set_batch_size(ctx.batch_size);
+        let pin = CpuPinGuard::on(core_for_run(cores, run_idx));
+        cache.prepare(&ctx.inputs);
         let (elapsed_time, result) = closure();
 
-        measurements[closure_idx].push(elapsed_time);
-        if result.is_some() {
-            result_samples[closure_idx] = result;
-        }
+        samples.record(closure_idx, pin.core_id(), elapsed_time, result);
 
         report_progress(completed, total_tasks, report_interval);
     }
 }
 
 fn report_progress(completed: usize, total: usize, interval: usize) {
-    if (completed + 1) % interval == 0 {
+    if (completed + 1).is_multiple_of(interval) {
         let pct = ((completed + 1) * 100) / total;
         print!("\r  Progress: {}%   ", pct);
         use std::io::Write;
@@ -0,0 +1,429 @@
+//! Statistical tests and confidence intervals for benchmark results.
+//!
+//! Welch's t-test only needs summary statistics (mean, standard deviation,
+//! sample count), so results loaded from snapshot files can be compared
+//! without their raw samples. Bootstrap intervals and the Mann-Whitney U
+//! test work on the raw samples of the current run.
+
+use super::bench::SeededRng;
+
+/// Confidence level of all reported intervals
+pub const CONFIDENCE_LEVEL: f64 = 0.95;
+
+/// Number of bootstrap resamples
+pub const BOOTSTRAP_RESAMPLES: usize = 1000;
+
+/// Fixed seed so that intervals are reproducible for identical samples
+const BOOTSTRAP_SEED: u64 = 0x5EED_B007;
+
+/// Summary of a sample: mean, sample standard deviation (N-1) and count
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub n: usize,
+}
+
+/// Welch's unequal-variance t-test.
+///
+/// # Returns
+/// The two-sided p-value, or `None` if either sample has fewer than 2 values.
+/// Identical constant samples yield `Some(1.0)`; different constant samples `Some(0.0)`.
+pub fn welch_t_test(a: Summary, b: Summary) -> Option<f64> {
+    if a.n < 2 || b.n < 2 {
+        return None;
+    }
+
+    let va = a.std_dev * a.std_dev / a.n as f64;
+    let vb = b.std_dev * b.std_dev / b.n as f64;
+    let se2 = va + vb;
+
+    if se2 <= 0.0 {
+        return Some(if a.mean == b.mean { 1.0 } else { 0.0 });
+    }
+
+    let t = (a.mean - b.mean) / se2.sqrt();
+    let df = se2 * se2 / (va * va / (a.n - 1) as f64 + vb * vb / (b.n - 1) as f64);
+
+    Some(student_t_two_sided(t, df))
+}
+
+/// Two-sided p-value of Student's t distribution: P(|T| >= |t|)
+pub fn student_t_two_sided(t: f64, df: f64) -> f64 {
+    if !t.is_finite() {
+        return 0.0;
+    }
+    let x = df / (df + t * t);
+    regularized_incomplete_beta(x, df / 2.0, 0.5).clamp(0.0, 1.0)
+}
+
+/// Mann-Whitney U test (normal approximation with tie correction).
+///
+/// # Returns
+/// The two-sided p-value, or `None` if either sample is empty.
+pub fn mann_whitney_u(a: &[u64], b: &[u64]) -> Option<f64> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+
+    let n1 = a.len() as f64;
+    let n2 = b.len() as f64;
+    let n = n1 + n2;
+
+    let mut all: Vec<(u64, bool)> = a
+        .iter()
+        .map(|&v| (v, true))
+        .chain(b.iter().map(|&v| (v, false)))
+        .collect();
+    all.sort_unstable_by_key(|&(v, _)| v);
+
+    // Average ranks for ties, and the tie correction term
+    let mut rank_sum_a = 0.0;
+    let mut tie_term = 0.0;
+    let mut i = 0;
+    while i < all.len() {
+        let mut j = i;
+        while j < all.len() && all[j].0 == all[i].0 {
+            j += 1;
+        }
+        let avg_rank = (i + j + 1) as f64 / 2.0;
+        let count = (j - i) as f64;
+        tie_term += count * count * count - count;
+        rank_sum_a += avg_rank * all[i..j].iter().filter(|(_, from_a)| *from_a).count() as f64;
+        i = j;
+    }
+
+    let u = rank_sum_a - n1 * (n1 + 1.0) / 2.0;
+    let mean_u = n1 * n2 / 2.0;
+    let var_u = n1 * n2 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)).max(1.0));
+
+    if var_u <= 0.0 {
+        return Some(1.0);
+    }
+
+    // Continuity correction
+    let z = ((u - mean_u).abs() - 0.5).max(0.0) / var_u.sqrt();
+    Some(normal_two_sided(z))
+}
+
+/// Two-sided p-value of the standard normal distribution: P(|Z| >= |z|)
+pub fn normal_two_sided(z: f64) -> f64 {
+    erfc(z.abs() / std::f64::consts::SQRT_2).clamp(0.0, 1.0)
+}
+
+/// Complementary error function (Chebyshev approximation, relative error < 1.2e-7)
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let r = t * (-z * z - 1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807
+                            + t * (-1.13520398
+                                + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277)))))))))
+        .exp();
+    if x >= 0.0 { r } else { 2.0 - r }
+}
+
+/// Half-width of the normal-approximation confidence interval of the mean,
+/// relative to the mean (cheap enough to evaluate while sampling).
+///
+/// # Returns
+/// `None` with fewer than 2 samples or a zero mean.
+pub fn relative_ci_half_width(samples: &[u64]) -> Option<f64> {
+    if samples.len() < 2 {
+        return None;
+    }
+    let n = samples.len() as f64;
+    let mean = samples.iter().map(|&v| v as f64).sum::<f64>() / n;
+    if mean <= 0.0 {
+        return None;
+    }
+    let variance = samples
+        .iter()
+        .map(|&v| (v as f64 - mean) * (v as f64 - mean))
+        .sum::<f64>()
+        / (n - 1.0);
+    Some(Z_95 * (variance / n).sqrt() / mean)
+}
+
+/// Two-sided 95% quantile of the standard normal distribution
+const Z_95: f64 = 1.959964;
+
+/// Percentile bootstrap confidence interval of a statistic.
+///
+/// # Returns
+/// `(low, high)` at `CONFIDENCE_LEVEL`, or `None` with fewer than 2 samples.
+pub fn bootstrap_ci(samples: &[u64], statistic: fn(&mut [u64]) -> f64) -> Option<(f64, f64)> {
+    if samples.len() < 2 {
+        return None;
+    }
+
+    let mut rng = SeededRng::new(BOOTSTRAP_SEED);
+    let mut resample = vec![0u64; samples.len()];
+    let mut estimates: Vec<f64> = (0..BOOTSTRAP_RESAMPLES)
+        .map(|_| {
+            fill_resample(samples, &mut resample, &mut rng);
+            statistic(&mut resample)
+        })
+        .collect();
+
+    Some(percentile_interval(&mut estimates))
+}
+
+/// Percentile bootstrap confidence interval of `mean(baseline) / mean(variant)`.
+pub fn bootstrap_speedup_ci(baseline: &[u64], variant: &[u64]) -> Option<(f64, f64)> {
+    if baseline.len() < 2 || variant.len() < 2 {
+        return None;
+    }
+
+    let mut rng = SeededRng::new(BOOTSTRAP_SEED);
+    let mut base_resample = vec![0u64; baseline.len()];
+    let mut var_resample = vec![0u64; variant.len()];
+    let mut estimates: Vec<f64> = (0..BOOTSTRAP_RESAMPLES)
+        .filter_map(|_| {
+            fill_resample(baseline, &mut base_resample, &mut rng);
+            fill_resample(variant, &mut var_resample, &mut rng);
+            let var_mean = mean(&mut var_resample);
+            (var_mean > 0.0).then(|| mean(&mut base_resample) / var_mean)
+        })
+        .collect();
+
+    if estimates.is_empty() {
+        return None;
+    }
+    Some(percentile_interval(&mut estimates))
+}
+
+fn fill_resample(samples: &[u64], out: &mut [u64], rng: &mut SeededRng) {
+    for slot in out.iter_mut() {
+        *slot = samples[(rng.next_u64() >> 33) as usize % samples.len()];
+    }
+}
+
+fn percentile_interval(estimates: &mut [f64]) -> (f64, f64) {
+    estimates.sort_unstable_by(|a, b| a.total_cmp(b));
+    let tail = (1.0 - CONFIDENCE_LEVEL) / 2.0;
+    let last = estimates.len() - 1;
+    let low = estimates[((last as f64) * tail).round() as usize];
+    let high = estimates[((last as f64) * (1.0 - tail)).round() as usize];
+    (low, high)
+}
+
+/// Arithmetic mean (usable as a bootstrap statistic)
+pub fn mean(values: &mut [u64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().map(|&v| v as f64).sum::<f64>() / values.len() as f64
+}
+
+/// Upper median, consistent with `VariantResult::median_time` (usable as a bootstrap statistic)
+pub fn median(values: &mut [u64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mid = values.len() / 2;
+    *values.select_nth_unstable(mid).1 as f64
+}
+
+/// Speedup of a variant over the baseline variant, with its uncertainty
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpeedupEstimate {
+    /// Baseline mean / variant mean
+    pub speedup: f64,
+    /// Bootstrap confidence interval of the speedup
+    pub ci: Option<(f64, f64)>,
+    /// Mann-Whitney U two-sided p-value against the baseline samples
+    pub p_value: Option<f64>,
+}
+
+impl SpeedupEstimate {
+    /// Estimate the speedup of `variant` over `baseline` from their raw samples
+    pub fn from_samples(baseline_mean: f64, variant_mean: f64, baseline: &[u64], variant: &[u64]) -> Self {
+        Self {
+            speedup: if variant_mean > 0.0 { baseline_mean / variant_mean } else { 0.0 },
+            ci: bootstrap_speedup_ci(baseline, variant),
+            p_value: mann_whitney_u(baseline, variant),
+        }
+    }
+}
+
+/// Natural logarithm of the gamma function (Lanczos approximation)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut ser = 1.000000000190015;
+    for (j, c) in COEFFS.iter().enumerate() {
+        ser += c / (x + 1.0 + j as f64);
+    }
+    -tmp + (2.5066282746310005 * ser / x).ln()
+}
+
+/// Regularized incomplete beta function I_x(a, b)
+fn regularized_incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    let front = ln_front.exp();
+
+    // Use the continued fraction where it converges quickly
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - front * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+/// Continued fraction for the incomplete beta function (modified Lentz's method)
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const MAX_ITER: usize = 300;
+    const EPS: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    let qab = a + b;
+    let qap = a + 1.0;
+    let qam = a - 1.0;
+    let mut c = 1.0;
+    let mut d = 1.0 - qab * x / qap;
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..=MAX_ITER {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        h *= d * c;
+
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let del = d * c;
+        h *= del;
+
+        if (del - 1.0).abs() < EPS {
+            break;
+        }
+    }
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64, tol: f64) -> bool {
+        (a - b).abs() < tol
+    }
+
+    #[test]
+    fn test_student_t_known_values() {
+        // Reference values from standard t tables
+        assert!(close(student_t_two_sided(2.228, 10.0), 0.05, 1e-3));
+        assert!(close(student_t_two_sided(1.96, 1e6), 0.05, 1e-3));
+        assert!(close(student_t_two_sided(0.0, 5.0), 1.0, 1e-9));
+        assert!(student_t_two_sided(50.0, 30.0) < 1e-20);
+    }
+
+    #[test]
+    fn test_normal_two_sided() {
+        assert!(close(normal_two_sided(1.959964), 0.05, 1e-6));
+        assert!(close(normal_two_sided(0.0), 1.0, 1e-6));
+        assert!(close(normal_two_sided(-2.575829), 0.01, 1e-6));
+    }
+
+    #[test]
+    fn test_mann_whitney_u() {
+        let a: Vec<u64> = (100..130).collect();
+        let shifted: Vec<u64> = (140..170).collect();
+        let overlapping: Vec<u64> = (101..131).collect();
+
+        assert!(mann_whitney_u(&a, &shifted).unwrap() < 1e-6);
+        assert!(mann_whitney_u(&a, &overlapping).unwrap() > 0.5);
+        assert_eq!(mann_whitney_u(&a, &[]), None);
+        assert!(close(mann_whitney_u(&[5, 5, 5], &[5, 5, 5]).unwrap(), 1.0, 1e-9));
+    }
+
+    #[test]
+    fn test_relative_ci_half_width() {
+        assert_eq!(relative_ci_half_width(&[100]), None);
+        assert_eq!(relative_ci_half_width(&[0, 0, 0]), None);
+        assert_eq!(relative_ci_half_width(&[100, 100, 100]), Some(0.0));
+
+        // sd = 10, n = 100, mean = 100: 1.96 * 10 / 10 / 100
+        let samples: Vec<u64> = (0..100).map(|i| if i % 2 == 0 { 90 } else { 110 }).collect();
+        let width = relative_ci_half_width(&samples).unwrap();
+        assert!(close(width, Z_95 * (100.0 * 100.0 / 99.0f64).sqrt() / 10.0 / 100.0, 1e-12));
+    }
+
+    #[test]
+    fn test_bootstrap_ci() {
+        let samples: Vec<u64> = (0..200).map(|i| 1000 + (i * 37) % 100).collect();
+        let sample_mean = mean(&mut samples.clone());
+
+        let (low, high) = bootstrap_ci(&samples, mean).unwrap();
+        assert!(low < sample_mean && sample_mean < high);
+        assert!(high - low < 20.0);
+
+        let (low, high) = bootstrap_ci(&samples, median).unwrap();
+        assert!(low <= high);
+        assert_eq!(bootstrap_ci(&[1], mean), None);
+
+        // Same data on both sides: interval around 1.0
+        let (low, high) = bootstrap_speedup_ci(&samples, &samples).unwrap();
+        assert!(low < 1.0 && 1.0 < high);
+
+        // Deterministic for identical input
+        assert_eq!(bootstrap_ci(&samples, mean), bootstrap_ci(&samples, mean));
+    }
+
+    #[test]
+    fn test_welch_t_test() {
+        let a = Summary { mean: 100.0, std_dev: 5.0, n: 30 };
+        let same = Summary { mean: 100.5, std_dev: 5.0, n: 30 };
+        let faster = Summary { mean: 80.0, std_dev: 5.0, n: 30 };
+
+        assert!(welch_t_test(a, same).unwrap() > 0.5);
+        assert!(welch_t_test(a, faster).unwrap() < 1e-6);
+        assert_eq!(welch_t_test(a, Summary { n: 1, ..a }), None);
+
+        let constant = Summary { mean: 10.0, std_dev: 0.0, n: 5 };
+        assert_eq!(welch_t_test(constant, constant), Some(1.0));
+        assert_eq!(welch_t_test(constant, Summary { mean: 11.0, ..constant }), Some(0.0));
+    }
+}
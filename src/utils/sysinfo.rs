@@ -0,0 +1,192 @@
+//! Machine and build information recorded alongside benchmark results.
+//!
+//! Results are only comparable on the same hardware and with the same
+//! toolchain, so every saved run carries a description of the machine it
+//! was measured on and of the binary that measured it.
+
+use super::json::JsonValue;
+
+/// Description of the machine a benchmark ran on
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MachineInfo {
+    /// CPU model string (e.g. "AMD Ryzen 9 7950X 16-Core Processor")
+    pub cpu_model: String,
+    /// Target operating system
+    pub os: String,
+    /// Target architecture
+    pub arch: String,
+    /// Kernel name and release (e.g. "Linux 6.8.0-45-generic")
+    pub kernel: Option<String>,
+    /// Number of online logical cores
+    pub logical_cores: usize,
+    /// C compiler used for C variants, if any
+    pub c_compiler: Option<String>,
+}
+
+impl MachineInfo {
+    /// Detect information about the current machine
+    pub fn detect() -> Self {
+        Self {
+            cpu_model: cpu_model().unwrap_or_else(|| "unknown".to_string()),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            kernel: kernel(),
+            logical_cores: super::cpu_affinity::get_core_ids()
+                .map(|c| c.len())
+                .unwrap_or(0),
+            c_compiler: super::C_COMPILER_NAME.map(str::to_string),
+        }
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::object()
+            .with("cpu_model", self.cpu_model.as_str())
+            .with("os", self.os.as_str())
+            .with("arch", self.arch.as_str())
+            .with("kernel", self.kernel.clone())
+            .with("logical_cores", self.logical_cores)
+            .with("c_compiler", self.c_compiler.clone())
+    }
+
+    pub fn from_json(value: &JsonValue) -> Self {
+        let text = |key: &str| {
+            value
+                .get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        Self {
+            cpu_model: text("cpu_model"),
+            os: text("os"),
+            arch: text("arch"),
+            kernel: value.get("kernel").and_then(|v| v.as_str()).map(str::to_string),
+            logical_cores: value
+                .get("logical_cores")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0) as usize,
+            c_compiler: value
+                .get("c_compiler")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        }
+    }
+}
+
+/// Description of the benchmark binary
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BuildInfo {
+    /// `rustc --version` output
+    pub rustc_version: String,
+    /// Target features enabled at build time (e.g. "sse2", "avx2")
+    pub target_features: Vec<String>,
+    /// Enabled cargo features of this crate
+    pub cargo_features: Vec<String>,
+    /// "release" or "debug"
+    pub profile: String,
+}
+
+impl BuildInfo {
+    /// Information about the running binary
+    pub fn current() -> Self {
+        let cargo_features = [
+            ("c_impl", cfg!(feature = "c_impl")),
+        ];
+
+        Self {
+            rustc_version: super::RUSTC_VERSION.unwrap_or("unknown").to_string(),
+            target_features: super::TARGET_FEATURES
+                .unwrap_or_default()
+                .split(',')
+                .filter(|f| !f.is_empty())
+                .map(str::to_string)
+                .collect(),
+            cargo_features: cargo_features
+                .iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(name, _)| name.to_string())
+                .collect(),
+            profile: if cfg!(debug_assertions) { "debug" } else { "release" }.to_string(),
+        }
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::object()
+            .with("rustc_version", self.rustc_version.as_str())
+            .with("target_features", self.target_features.clone())
+            .with("cargo_features", self.cargo_features.clone())
+            .with("profile", self.profile.as_str())
+    }
+
+    pub fn from_json(value: &JsonValue) -> Self {
+        let list = |key: &str| -> Vec<String> {
+            value
+                .get(key)
+                .and_then(|v| v.as_array())
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect()
+        };
+        Self {
+            rustc_version: value
+                .get("rustc_version")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            target_features: list("target_features"),
+            cargo_features: list("cargo_features"),
+            profile: value
+                .get("profile")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+}
+
+/// Read the kernel name and release
+#[cfg(unix)]
+fn kernel() -> Option<String> {
+    let field = |raw: &[libc::c_char]| unsafe {
+        std::ffi::CStr::from_ptr(raw.as_ptr()).to_string_lossy().into_owned()
+    };
+    unsafe {
+        let mut uts: libc::utsname = std::mem::zeroed();
+        if libc::uname(&mut uts) != 0 {
+            return None;
+        }
+        Some(format!("{} {}", field(&uts.sysname), field(&uts.release)))
+    }
+}
+
+#[cfg(not(unix))]
+fn kernel() -> Option<String> {
+    None
+}
+
+/// Read the CPU model name
+#[cfg(target_os = "linux")]
+fn cpu_model() -> Option<String> {
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").ok()?;
+    cpuinfo
+        .lines()
+        .find(|line| line.starts_with("model name") || line.starts_with("Model"))
+        .and_then(|line| line.split_once(':'))
+        .map(|(_, value)| value.trim().to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn cpu_model() -> Option<String> {
+    let output = std::process::Command::new("sysctl")
+        .args(["-n", "machdep.cpu.brand_string"])
+        .output()
+        .ok()?;
+    let model = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!model.is_empty()).then_some(model)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn cpu_model() -> Option<String> {
+    std::env::var("PROCESSOR_IDENTIFIER").ok()
+}
@@ -0,0 +1,105 @@
+//! Structured variant metadata.
+//!
+//! Each variant carries the components of the README naming convention
+//! (`[<language>-]<architecture>[-<simd>][-<technique>]`) as separate fields,
+//! so that variants can be sorted, displayed and selected (see `filter`)
+//! without parsing names.
+
+/// Implementation language of a variant
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Language {
+    #[default]
+    Rust,
+    C,
+}
+
+impl Language {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::C => "c",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "rust" | "rs" => Some(Language::Rust),
+            "c" => Some(Language::C),
+            _ => None,
+        }
+    }
+}
+
+/// Language, architecture, SIMD level and technique of a variant.
+///
+/// Built with `VariantTags::rust()` or `VariantTags::c()` followed by the
+/// optional components, e.g. `VariantTags::rust().arch("x86_64").simd("avx2")`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VariantTags {
+    pub lang: Language,
+    /// Target architecture (`None` for portable code)
+    pub arch: Option<&'static str>,
+    /// Vector instruction set (`None` for scalar code)
+    pub simd: Option<&'static str>,
+    /// Additional optimization technique (e.g. "asm")
+    pub technique: Option<&'static str>,
+}
+
+impl VariantTags {
+    /// Portable Rust implementation
+    pub const fn rust() -> Self {
+        Self { lang: Language::Rust, arch: None, simd: None, technique: None }
+    }
+
+    /// Portable C implementation
+    pub const fn c() -> Self {
+        Self { lang: Language::C, arch: None, simd: None, technique: None }
+    }
+
+    pub const fn arch(self, arch: &'static str) -> Self {
+        Self { arch: Some(arch), ..self }
+    }
+
+    pub const fn simd(self, simd: &'static str) -> Self {
+        Self { simd: Some(simd), ..self }
+    }
+
+    pub const fn technique(self, technique: &'static str) -> Self {
+        Self { technique: Some(technique), ..self }
+    }
+}
+
+impl std::fmt::Display for VariantTags {
+    /// Present components separated by spaces (e.g. "c x86_64 sse2")
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.lang.as_str())?;
+        for component in [self.arch, self.simd, self.technique].into_iter().flatten() {
+            write!(f, " {}", component)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_parse() {
+        assert_eq!(Language::parse("C"), Some(Language::C));
+        assert_eq!(Language::parse("rust"), Some(Language::Rust));
+        assert_eq!(Language::parse("go"), None);
+        assert_eq!(Language::parse(Language::C.as_str()), Some(Language::C));
+    }
+
+    #[test]
+    fn test_builder() {
+        let tags = VariantTags::c().arch("x86_64").simd("sse2");
+        assert_eq!(tags.lang, Language::C);
+        assert_eq!(tags.arch, Some("x86_64"));
+        assert_eq!(tags.simd, Some("sse2"));
+        assert_eq!(tags.technique, None);
+        assert_eq!(tags.to_string(), "c x86_64 sse2");
+        assert_eq!(VariantTags::rust().to_string(), "rust");
+    }
+}
@@ -0,0 +1,143 @@
+//! Work done per call, for throughput reporting.
+//!
+//! An algorithm can declare how much work one call does at a given size
+//! (`AlgorithmRunner::work`): bytes read, elements processed or operations.
+//! Dividing by the average cost gives throughput in work per measurement unit
+//! (bytes/cycle, elements/ns, ...). Across sizes, the bytes/cycle of a variant
+//! drop once its working set leaves a cache level: the size where it becomes
+//! memory bound.
+
+use super::bench::unit_name;
+
+/// Kind of work an algorithm does
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WorkUnit {
+    /// Bytes of input read
+    Bytes,
+    /// Input elements processed
+    Elements,
+    /// Arithmetic operations (e.g. floating-point operations)
+    Operations,
+}
+
+impl WorkUnit {
+    pub const ALL: [WorkUnit; 3] = [WorkUnit::Bytes, WorkUnit::Elements, WorkUnit::Operations];
+
+    /// Name used in exports ("bytes", "elements", "ops")
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkUnit::Bytes => "bytes",
+            WorkUnit::Elements => "elements",
+            WorkUnit::Operations => "ops",
+        }
+    }
+
+    /// Short name used in table headers ("B", "elem", "op")
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            WorkUnit::Bytes => "B",
+            WorkUnit::Elements => "elem",
+            WorkUnit::Operations => "op",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|unit| unit.as_str() == name)
+    }
+
+    /// Table header of the throughput in this unit (e.g. "B/cycle")
+    pub fn rate_label(&self) -> String {
+        format!("{}/{}", self.symbol(), singular(unit_name()))
+    }
+
+    /// CSV column of the throughput in this unit (e.g. "bytes_per_cycle")
+    pub fn rate_column(&self) -> String {
+        format!("{}_per_{}", self.as_str(), singular(unit_name()))
+    }
+}
+
+/// Amount of one kind of work done by a single call
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Work {
+    pub unit: WorkUnit,
+    pub amount: u64,
+}
+
+impl Work {
+    pub fn bytes(amount: usize) -> Self {
+        Self { unit: WorkUnit::Bytes, amount: amount as u64 }
+    }
+
+    pub fn elements(amount: usize) -> Self {
+        Self { unit: WorkUnit::Elements, amount: amount as u64 }
+    }
+
+    pub fn operations(amount: usize) -> Self {
+        Self { unit: WorkUnit::Operations, amount: amount as u64 }
+    }
+
+    /// Work per measurement unit, for a call costing `cost` (cycles or nanoseconds)
+    pub fn rate(&self, cost: f64) -> Option<f64> {
+        (cost > 0.0).then(|| self.amount as f64 / cost)
+    }
+}
+
+/// Throughput in `unit` of a call costing `cost`, if `work` declares that unit
+pub fn rate_of(work: &[Work], unit: WorkUnit, cost: f64) -> Option<f64> {
+    work.iter().find(|w| w.unit == unit).and_then(|w| w.rate(cost))
+}
+
+/// Distinct `units`, in `WorkUnit` order (one column each)
+pub fn distinct_units(units: impl IntoIterator<Item = WorkUnit>) -> Vec<WorkUnit> {
+    let mut units: Vec<WorkUnit> = units.into_iter().collect();
+    units.sort();
+    units.dedup();
+    units
+}
+
+/// Format a throughput with 3 significant digits
+pub fn format_rate(rate: f64) -> String {
+    if rate >= 100.0 {
+        format!("{:.0}", rate)
+    } else if rate >= 10.0 {
+        format!("{:.1}", rate)
+    } else if rate >= 1.0 {
+        format!("{:.2}", rate)
+    } else {
+        format!("{:.3}", rate)
+    }
+}
+
+/// "cycles" -> "cycle", "ns" unchanged
+fn singular(unit: &str) -> &str {
+    unit.strip_suffix('s').filter(|u| u.len() > 1).unwrap_or(unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rates() {
+        let work = vec![Work::bytes(4096), Work::elements(512)];
+        assert_eq!(rate_of(&work, WorkUnit::Bytes, 1024.0), Some(4.0));
+        assert_eq!(rate_of(&work, WorkUnit::Elements, 1024.0), Some(0.5));
+        assert_eq!(rate_of(&work, WorkUnit::Operations, 1024.0), None);
+        assert_eq!(rate_of(&work, WorkUnit::Bytes, 0.0), None);
+
+        let units = distinct_units([Work::operations(1)].iter().chain(&work).map(|w| w.unit));
+        assert_eq!(units, vec![WorkUnit::Bytes, WorkUnit::Elements, WorkUnit::Operations]);
+    }
+
+    #[test]
+    fn test_names() {
+        for unit in WorkUnit::ALL {
+            assert_eq!(WorkUnit::parse(unit.as_str()), Some(unit));
+        }
+        assert_eq!(singular("cycles"), "cycle");
+        assert_eq!(singular("ns"), "ns");
+        assert_eq!(format_rate(4.56789), "4.57");
+        assert_eq!(format_rate(0.01234), "0.012");
+        assert_eq!(format_rate(123.4), "123");
+    }
+}
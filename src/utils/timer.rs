@@ -1,7 +1,7 @@
 //! Unified timing system for micro-benchmarks.
 //!
 //! This module provides the single timing infrastructure with:
-//! - Support for both CPU cycles and wall-clock time (via features)
+//! - Support for cycle counters and wall-clock time (via `--clock`)
 //! - Automatic CPU core pinning for stable measurements
 //! - Randomized variant execution to avoid ordering bias
 //! - All raw measurements preserved for external analysis
@@ -10,7 +10,15 @@ use std::hint::black_box;
 use std::time::Duration;
 
 pub use super::cpu_affinity::{pin_to_current_core, unpin, CpuPinGuard};
-use super::bench::{shuffle, time_seed, to_nanos, Measurement};
+use super::cpu_affinity::benchmark_cores;
+use super::bench::{
+    calibrate_overhead, shuffle, subtract_overhead, time_seed, Measurement, CALIBRATION_SAMPLES,
+};
+use super::cache::CacheMode;
+use super::clock::ClockKind;
+use super::perf::{self, CounterSample, CounterStats};
+use super::stats::{self, SpeedupEstimate};
+use super::tags::VariantTags;
 
 // ============================================================================
 // Configuration
@@ -26,6 +34,58 @@ pub enum PinStrategy {
     PerExecution,
 }
 
+impl PinStrategy {
+    /// Name used on the command line and in exports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PinStrategy::Global => "global",
+            PinStrategy::PerExecution => "per-call",
+        }
+    }
+
+    /// Parse a strategy name ("global", "per-call" or "per-execution")
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "global" => Some(PinStrategy::Global),
+            "per-call" | "per-execution" => Some(PinStrategy::PerExecution),
+            _ => None,
+        }
+    }
+}
+
+/// How many iterations `measure_batched!` closures run per sample
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Batching {
+    /// One iteration per sample
+    Off,
+    /// Smallest power of two that makes every variant of a group take well above
+    /// the timer overhead per sample
+    #[default]
+    Auto,
+    /// Fixed number of iterations per sample
+    Fixed(usize),
+}
+
+impl Batching {
+    /// Name used on the command line and in exports
+    pub fn as_string(&self) -> String {
+        match self {
+            Batching::Off => "off".to_string(),
+            Batching::Auto => "auto".to_string(),
+            Batching::Fixed(n) => n.to_string(),
+        }
+    }
+
+    /// Parse "off", "auto" or a positive iteration count
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "off" => Some(Batching::Off),
+            "auto" => Some(Batching::Auto),
+            n => n.parse().ok().filter(|&n| n > 0).map(Batching::Fixed),
+        }
+    }
+}
+
 /// Configuration for timing measurements
 #[derive(Clone, Debug)]
 pub struct TimingConfig {
@@ -35,18 +95,63 @@ pub struct TimingConfig {
     pub warmup_iterations: usize,
     /// CPU pinning strategy (default: PerExecution)
     pub pin_strategy: PinStrategy,
+    /// Collect hardware performance counters around each measurement (default: false, Linux only)
+    pub hw_counters: bool,
+    /// Adaptive sampling: keep measuring a variant until the 95% CI half-width of its
+    /// mean, relative to the mean, is below this value (e.g. 0.01 for ±1%)
+    pub target_precision: Option<f64>,
+    /// Adaptive sampling: wall-clock budget per variant (default with a target precision: 2s)
+    pub time_budget: Option<Duration>,
+    /// Adaptive sampling: upper bound on the samples kept per variant (default: 100000)
+    pub max_runs_per_variant: usize,
+    /// Subtract the calibrated cost of an empty measurement from every sample (default: false)
+    pub subtract_overhead: bool,
+    /// Iterations per sample for variants timed with `measure_batched!` (default: Auto)
+    pub batching: Batching,
+    /// Cache state before each sample (default: Hot)
+    pub cache: CacheMode,
+    /// Clock read around each sample (default: rdtsc where available)
+    pub clock: ClockKind,
+    /// Cores to pin to; the runs of each variant rotate through them
+    /// (default: empty, for an isolated core if any, else the current core)
+    pub cores: Vec<usize>,
 }
 
+/// Time budget per variant when only a target precision is given
+pub const DEFAULT_TIME_BUDGET: Duration = Duration::from_secs(2);
+
 impl Default for TimingConfig {
     fn default() -> Self {
         Self {
             runs_per_variant: 30,
             warmup_iterations: 10,
             pin_strategy: PinStrategy::default(),
+            hw_counters: false,
+            target_precision: None,
+            time_budget: None,
+            max_runs_per_variant: 100_000,
+            subtract_overhead: false,
+            batching: Batching::default(),
+            cache: CacheMode::default(),
+            clock: ClockKind::default(),
+            cores: Vec::new(),
         }
     }
 }
 
+impl TimingConfig {
+    /// Whether variants are sampled until a precision or time budget is reached,
+    /// with `runs_per_variant` as the initial (minimum) number of runs
+    pub fn is_adaptive(&self) -> bool {
+        self.target_precision.is_some() || self.time_budget.is_some()
+    }
+
+    /// Time budget per variant in adaptive mode
+    pub fn effective_time_budget(&self) -> Duration {
+        self.time_budget.unwrap_or(DEFAULT_TIME_BUDGET)
+    }
+}
+
 /// A variant to be measured
 pub struct Variant<'a> {
     /// Unique name of the variant
@@ -77,10 +182,112 @@ pub struct VariantResult {
     pub max_time: Duration,
     /// Standard deviation
     pub std_dev: Duration,
+    /// Precise standard deviation in nanoseconds/cycles as f64
+    pub std_dev_f64: f64,
     /// Number of iterations performed
     pub iterations: usize,
     /// Sample result value (for verification) - only for algorithms that have meaningful results
     pub result_sample: Option<f64>,
+    /// Average hardware counter values per call (when counters were enabled)
+    pub counters: Option<CounterStats>,
+    /// Bootstrap confidence interval of the mean (nanoseconds/cycles)
+    pub mean_ci: Option<(f64, f64)>,
+    /// Bootstrap confidence interval of the median (nanoseconds/cycles)
+    pub median_ci: Option<(f64, f64)>,
+    /// Speedup over the first variant of the group (see `annotate_speedups`)
+    pub speedup: Option<SpeedupEstimate>,
+    /// Sorted measurements the statistics were computed from (empty for loaded snapshots)
+    pub samples: Vec<u64>,
+    /// The mean cannot be told apart from an empty measurement (see `bench::TimerOverhead`)
+    pub below_noise_floor: bool,
+    /// Iterations per sample (`measure_batched!`); statistics are per iteration
+    pub batch_size: usize,
+    /// Language, architecture, SIMD level and technique of the variant
+    pub tags: VariantTags,
+    /// Mean per core the samples ran on, by core (empty if the cores are unknown)
+    pub per_core: Vec<CoreMean>,
+}
+
+/// Mean of the samples of a variant that ran on one core
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CoreMean {
+    pub core: usize,
+    /// Mean per iteration (nanoseconds/cycles)
+    pub mean: f64,
+    pub samples: usize,
+}
+
+impl CoreMean {
+    /// Per-core means of `(core, value)` samples, sorted by core
+    pub fn from_samples(samples: impl IntoIterator<Item = (usize, u64)>) -> Vec<CoreMean> {
+        let mut per_core: Vec<CoreMean> = Vec::new();
+        for (core, value) in samples {
+            match per_core.iter_mut().find(|c| c.core == core) {
+                Some(entry) => {
+                    entry.mean += value as f64;
+                    entry.samples += 1;
+                }
+                None => per_core.push(CoreMean { core, mean: value as f64, samples: 1 }),
+            }
+        }
+        per_core.iter_mut().for_each(|c| c.mean /= c.samples as f64);
+        per_core.sort_by_key(|c| c.core);
+        per_core
+    }
+}
+
+impl VariantResult {
+    /// Fill `mean_ci` and `median_ci` from `samples`
+    pub fn compute_intervals(&mut self) {
+        self.mean_ci = stats::bootstrap_ci(&self.samples, stats::mean);
+        self.median_ci = stats::bootstrap_ci(&self.samples, stats::median);
+    }
+
+    /// Convert statistics computed on whole batches to per-iteration values.
+    ///
+    /// `samples` keep the per-batch values, which stay comparable within a group
+    /// since all its variants share the batch size.
+    pub fn scale_to_batch(&mut self, batch_size: usize) {
+        self.batch_size = batch_size;
+        if batch_size <= 1 {
+            return;
+        }
+        let n = batch_size as f64;
+        let scale = |d: Duration| Duration::from_nanos((d.as_nanos() as f64 / n).round() as u64);
+        let scale_interval = |ci: Option<(f64, f64)>| ci.map(|(low, high)| (low / n, high / n));
+
+        self.avg_nanos_f64 /= n;
+        self.avg_time = scale(self.avg_time);
+        self.median_time = scale(self.median_time);
+        self.min_time = scale(self.min_time);
+        self.max_time = scale(self.max_time);
+        self.std_dev = scale(self.std_dev);
+        self.std_dev_f64 /= n;
+        self.mean_ci = scale_interval(self.mean_ci);
+        self.median_ci = scale_interval(self.median_ci);
+        if let Some(counters) = &mut self.counters {
+            counters.means.iter_mut().flatten().for_each(|m| *m /= n);
+        }
+        self.per_core.iter_mut().for_each(|c| c.mean /= n);
+    }
+}
+
+/// Compute the speedup of every result over the first one (the baseline).
+pub fn annotate_speedups(results: &mut [VariantResult]) {
+    let Some(baseline) = results.first() else {
+        return;
+    };
+    let baseline_avg = baseline.avg_nanos_f64;
+    let baseline_samples = baseline.samples.clone();
+
+    for result in results.iter_mut() {
+        result.speedup = Some(SpeedupEstimate::from_samples(
+            baseline_avg,
+            result.avg_nanos_f64,
+            &baseline_samples,
+            &result.samples,
+        ));
+    }
 }
 
 /// Measure multiple variants with randomized execution order.
@@ -89,7 +296,7 @@ pub struct VariantResult {
 /// 1. Warms up all variants
 /// 2. Creates a randomized task schedule
 /// 3. Measures each variant with CPU pinning
-/// 4. Returns results for all variants
+/// 4. Returns results for all variants, with speedups relative to the first one
 ///
 /// # Arguments
 /// * `variants` - List of variants to measure
@@ -126,25 +333,48 @@ pub fn measure_variants(
     let mut measurements: Vec<Vec<Measurement>> = (0..variants.len())
         .map(|_| Vec::with_capacity(samples))
         .collect();
+    let mut counter_samples: Vec<Vec<CounterSample>> = vec![Vec::new(); variants.len()];
     let mut result_samples: Vec<Option<f64>> = vec![None; variants.len()];
 
-    let _global_pin = (config.pin_strategy == PinStrategy::Global).then(CpuPinGuard::new);
+    let cores = benchmark_cores(&config.cores);
+    let overhead = config.subtract_overhead.then(|| calibrate_overhead(CALIBRATION_SAMPLES, cores.first().copied()));
+
+    let counters_enabled = config.hw_counters && perf::enable().is_ok();
+    let _global_pin = (config.pin_strategy == PinStrategy::Global).then(|| CpuPinGuard::on(cores.first().copied()));
 
-    for (variant_idx, _) in tasks {
+    for (variant_idx, sample_idx) in tasks {
         let variant = &mut variants[variant_idx];
-        let _per_exec_pin = (config.pin_strategy == PinStrategy::PerExecution).then(CpuPinGuard::new);
+        // Samples of each variant rotate through the selected cores
+        let core = (!cores.is_empty()).then(|| cores[sample_idx % cores.len()]);
+        let _per_exec_pin = (config.pin_strategy == PinStrategy::PerExecution).then(|| CpuPinGuard::on(core));
         let (elapsed_time, result) = (variant.run)();
 
         measurements[variant_idx].push(elapsed_time);
+        if let Some(sample) = perf::take_last() {
+            counter_samples[variant_idx].push(sample);
+        }
         result_samples[variant_idx] = result;
     }
 
-    variants.into_iter().enumerate().map(|(idx, variant)| {
-            let times = std::mem::take(&mut measurements[idx]);
+    if counters_enabled {
+        perf::disable();
+    }
+
+    let mut results: Vec<VariantResult> = variants.into_iter().enumerate().map(|(idx, variant)| {
+            let mut times = std::mem::take(&mut measurements[idx]);
+            if let Some(overhead) = &overhead {
+                times.iter_mut().for_each(|m| *m = subtract_overhead(*m, overhead.median));
+            }
             let result_sample = result_samples[idx].take();
-            compute_variant_result(variant.name, variant.description, times, iterations, result_sample)
+            let mut result = compute_variant_result(variant.name, variant.description, times, iterations, result_sample);
+            result.counters = CounterStats::from_samples(&counter_samples[idx]);
+            result.below_noise_floor = overhead.is_some_and(|o| o.is_below_noise_floor(result.avg_nanos_f64, true));
+            result
         })
-        .collect()
+        .collect();
+
+    annotate_speedups(&mut results);
+    results
 }
 
 /// Compute statistics from raw measurements
@@ -165,12 +395,22 @@ fn compute_variant_result(
             min_time: Duration::ZERO,
             max_time: Duration::ZERO,
             std_dev: Duration::ZERO,
+            std_dev_f64: 0.0,
             iterations,
             result_sample: None,
+            counters: None,
+            mean_ci: None,
+            median_ci: None,
+            speedup: None,
+            samples: Vec::new(),
+            below_noise_floor: false,
+            batch_size: 1,
+            tags: VariantTags::default(),
+            per_core: Vec::new(),
         };
     }
 
-    let nanos: Vec<u64> = measurements.iter().map(|m| to_nanos(*m)).collect();
+    let nanos = measurements;
 
     let mut sorted = nanos.clone();
     sorted.sort();
@@ -191,9 +431,9 @@ fn compute_variant_result(
         })
         .sum::<f64>()
         / (nanos.len() - 1).max(1) as f64;
-    let std_dev_ns = variance.sqrt() as u64;
+    let std_dev_f64 = variance.sqrt();
 
-    VariantResult {
+    let mut result = VariantResult {
         name: name.to_string(),
         description: description.to_string(),
         avg_time: Duration::from_nanos(avg_ns),
@@ -201,10 +441,22 @@ fn compute_variant_result(
         median_time: Duration::from_nanos(median_ns),
         min_time: Duration::from_nanos(min_ns),
         max_time: Duration::from_nanos(max_ns),
-        std_dev: Duration::from_nanos(std_dev_ns),
+        std_dev: Duration::from_nanos(std_dev_f64 as u64),
+        std_dev_f64,
         iterations,
         result_sample,
-    }
+        counters: None,
+        mean_ci: None,
+        median_ci: None,
+        speedup: None,
+        samples: sorted,
+        below_noise_floor: false,
+        batch_size: 1,
+        tags: VariantTags::default(),
+        per_core: Vec::new(),
+    };
+    result.compute_intervals();
+    result
 }
 
 /// Calculate median from a slice of durations.
@@ -227,6 +479,51 @@ mod tests {
         assert!(results.is_empty());
     }
 
+    #[test]
+    fn test_batching_parse() {
+        assert_eq!(Batching::parse("auto"), Some(Batching::Auto));
+        assert_eq!(Batching::parse("off"), Some(Batching::Off));
+        assert_eq!(Batching::parse("512"), Some(Batching::Fixed(512)));
+        assert_eq!(Batching::parse("0"), None);
+        assert_eq!(Batching::parse("fast"), None);
+        assert_eq!(Batching::Fixed(512).as_string(), "512");
+    }
+
+    #[test]
+    fn test_scale_to_batch() {
+        use crate::measure_batched;
+
+        crate::utils::bench::set_batch_size(4);
+        let mut calls = 0;
+        let (_, last) = measure_batched!({
+            calls += 1;
+            calls
+        });
+        crate::utils::bench::set_batch_size(1);
+        assert_eq!((calls, last), (4, 4));
+
+        let mut result = compute_variant_result("batched", "", vec![Measurement::default(); 3], 3, None);
+        result.avg_nanos_f64 = 1000.0;
+        result.std_dev_f64 = 100.0;
+        result.mean_ci = Some((900.0, 1100.0));
+        result.scale_to_batch(100);
+
+        assert_eq!(result.batch_size, 100);
+        assert_eq!(result.avg_nanos_f64, 10.0);
+        assert_eq!(result.std_dev_f64, 1.0);
+        assert_eq!(result.mean_ci, Some((9.0, 11.0)));
+    }
+
+    #[test]
+    fn test_core_means() {
+        let per_core = CoreMean::from_samples([(3, 100), (1, 40), (3, 120), (1, 60)]);
+        assert_eq!(
+            per_core,
+            vec![CoreMean { core: 1, mean: 50.0, samples: 2 }, CoreMean { core: 3, mean: 110.0, samples: 2 }]
+        );
+        assert!(CoreMean::from_samples([]).is_empty());
+    }
+
     #[test]
     fn test_measure_variants_single() {
         use crate::measure;
@@ -244,6 +541,7 @@ mod tests {
             runs_per_variant: 5,
             warmup_iterations: 2,
             pin_strategy: PinStrategy::Global,
+            ..TimingConfig::default()
         };
 
         let results = measure_variants(variants, 100, &config);
@@ -279,6 +577,7 @@ mod tests {
             runs_per_variant: 5,
             warmup_iterations: 2,
             pin_strategy: PinStrategy::PerExecution,
+            ..TimingConfig::default()
         };
 
         let results = measure_variants(variants, 100, &config);
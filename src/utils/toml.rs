@@ -0,0 +1,377 @@
+//! Minimal TOML parser producing `JsonValue` documents.
+//!
+//! Used for benchmark configuration files (see `config`). Implemented manually
+//! to keep the dependency footprint small. Supports the subset that
+//! configuration needs: tables (`[a.b]`), dotted and quoted keys, basic and
+//! literal strings, integers, floats, booleans, (multi-line) arrays and inline
+//! tables. Arrays of tables, multi-line strings and dates are rejected.
+
+use super::json::JsonValue;
+
+/// Parse a TOML document into a JSON object
+pub fn parse(input: &str) -> Result<JsonValue, String> {
+    let mut parser = Parser {
+        chars: input.chars().collect(),
+        pos: 0,
+    };
+    let mut root = JsonValue::object();
+    let mut table: Vec<String> = Vec::new();
+
+    loop {
+        parser.skip_blank_lines();
+        if parser.peek().is_none() {
+            break;
+        }
+
+        if parser.peek() == Some('[') {
+            parser.pos += 1;
+            if parser.peek() == Some('[') {
+                return Err(parser.error("Arrays of tables are not supported"));
+            }
+            table = parser.parse_key()?;
+            parser.expect(']')?;
+            table_mut(&mut root, &table).map_err(|e| parser.error(&e))?;
+        } else {
+            let key = parser.parse_key()?;
+            parser.expect('=')?;
+            parser.skip_spaces();
+            let value = parser.parse_value()?;
+
+            let (name, parents) = key.split_last().expect("keys have at least one segment");
+            let path: Vec<String> = table.iter().chain(parents).cloned().collect();
+            let fields = table_mut(&mut root, &path).map_err(|e| parser.error(&e))?;
+            if fields.iter().any(|(k, _)| k == name) {
+                return Err(parser.error(&format!("Duplicate key '{}'", name)));
+            }
+            fields.push((name.clone(), value));
+        }
+        parser.expect_line_end()?;
+    }
+
+    Ok(root)
+}
+
+/// Fields of the table at `path`, creating missing tables on the way
+fn table_mut<'a>(root: &'a mut JsonValue, path: &[String]) -> Result<&'a mut Vec<(String, JsonValue)>, String> {
+    let mut current = root;
+    for segment in path {
+        let JsonValue::Object(fields) = current else {
+            return Err(format!("'{}' is not a table", segment));
+        };
+        let idx = match fields.iter().position(|(k, _)| k == segment) {
+            Some(idx) => idx,
+            None => {
+                fields.push((segment.clone(), JsonValue::object()));
+                fields.len() - 1
+            }
+        };
+        current = &mut fields[idx].1;
+    }
+    match current {
+        JsonValue::Object(fields) => Ok(fields),
+        _ => Err(format!("'{}' is not a table", path.join("."))),
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> String {
+        let line = self.chars[..self.pos.min(self.chars.len())].iter().filter(|&&c| c == '\n').count() + 1;
+        format!("{} (line {})", message, line)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_spaces(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        if self.peek() == Some('#') {
+            while !matches!(self.peek(), None | Some('\n')) {
+                self.pos += 1;
+            }
+        }
+    }
+
+    /// Skip whitespace, newlines and comments
+    fn skip_blank_lines(&mut self) {
+        loop {
+            self.skip_spaces();
+            self.skip_comment();
+            match self.peek() {
+                Some('\n' | '\r') => self.pos += 1,
+                _ => break,
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_spaces();
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected '{}'", expected)))
+        }
+    }
+
+    fn expect_line_end(&mut self) -> Result<(), String> {
+        self.skip_spaces();
+        self.skip_comment();
+        match self.peek() {
+            None | Some('\n' | '\r') => Ok(()),
+            Some(c) => Err(self.error(&format!("Unexpected '{}' after value", c))),
+        }
+    }
+
+    /// Parse a (possibly dotted) key into its segments
+    fn parse_key(&mut self) -> Result<Vec<String>, String> {
+        let mut segments = Vec::new();
+        loop {
+            self.skip_spaces();
+            let segment = match self.peek() {
+                Some('"') => self.parse_basic_string()?,
+                Some('\'') => self.parse_literal_string()?,
+                _ => {
+                    let start = self.pos;
+                    while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                        self.pos += 1;
+                    }
+                    if self.pos == start {
+                        return Err(self.error("Expected a key"));
+                    }
+                    self.chars[start..self.pos].iter().collect()
+                }
+            };
+            segments.push(segment);
+            self.skip_spaces();
+            if self.peek() == Some('.') {
+                self.pos += 1;
+            } else {
+                return Ok(segments);
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue, String> {
+        match self.peek() {
+            Some('"') => self.parse_basic_string().map(JsonValue::String),
+            Some('\'') => self.parse_literal_string().map(JsonValue::String),
+            Some('[') => self.parse_array(),
+            Some('{') => self.parse_inline_table(),
+            Some('t') => self.parse_literal("true", JsonValue::Bool(true)),
+            Some('f') => self.parse_literal("false", JsonValue::Bool(false)),
+            Some(c) if c.is_ascii_digit() || c == '+' || c == '-' => self.parse_number(),
+            _ => Err(self.error("Expected a value")),
+        }
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: JsonValue) -> Result<JsonValue, String> {
+        let end = self.pos + literal.chars().count();
+        if end <= self.chars.len() && self.chars[self.pos..end].iter().copied().eq(literal.chars()) {
+            self.pos = end;
+            Ok(value)
+        } else {
+            Err(self.error("Expected a value"))
+        }
+    }
+
+    fn parse_basic_string(&mut self) -> Result<String, String> {
+        if self.chars[self.pos..].starts_with(&['"', '"', '"']) {
+            return Err(self.error("Multi-line strings are not supported"));
+        }
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            match self.peek() {
+                None | Some('\n') => return Err(self.error("Unterminated string")),
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    let escaped = match self.peek() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('u') => {
+                            let hex: String = self.chars.get(self.pos + 1..self.pos + 5).unwrap_or(&[]).iter().collect();
+                            let c = u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("Invalid unicode escape"))?;
+                            self.pos += 4;
+                            c
+                        }
+                        _ => return Err(self.error("Invalid escape sequence")),
+                    };
+                    out.push(escaped);
+                    self.pos += 1;
+                }
+                Some(c) => {
+                    out.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    fn parse_literal_string(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                None | Some('\n') => return Err(self.error("Unterminated string")),
+                Some('\'') => {
+                    let s = self.chars[start..self.pos].iter().collect();
+                    self.pos += 1;
+                    return Ok(s);
+                }
+                Some(_) => self.pos += 1,
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, String> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.' | '_')) {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().filter(|&&c| c != '_').collect();
+        let digits = text.strip_prefix('+').unwrap_or(&text);
+        if let Ok(value) = digits.parse::<u64>() {
+            return Ok(JsonValue::UInt(value));
+        }
+        match text.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(JsonValue::Number(value)),
+            _ => Err(self.error(&format!("Invalid number '{}'", text))),
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue, String> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_blank_lines();
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(JsonValue::Array(items));
+            }
+            items.push(self.parse_value()?);
+            self.skip_blank_lines();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {}
+                _ => return Err(self.error("Expected ',' or ']' in array")),
+            }
+        }
+    }
+
+    fn parse_inline_table(&mut self) -> Result<JsonValue, String> {
+        self.pos += 1;
+        let mut table = JsonValue::object();
+        self.skip_spaces();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(table);
+        }
+        loop {
+            let key = self.parse_key()?;
+            self.expect('=')?;
+            self.skip_spaces();
+            let value = self.parse_value()?;
+            let (name, parents) = key.split_last().expect("keys have at least one segment");
+            let fields = table_mut(&mut table, parents).map_err(|e| self.error(&e))?;
+            fields.push((name.clone(), value));
+
+            self.skip_spaces();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(table);
+                }
+                _ => return Err(self.error("Expected ',' or '}' in inline table")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_document() {
+        let doc = parse(
+            r#"
+# Top-level settings
+runs = 1_000
+seed = 42
+precision = 0.5   # percent
+filter_outliers = true
+sizes = [
+    64,
+    1024,  # trailing comma
+]
+pin = "per-call"
+variants = ['dot_product/x86_64-*', "*/original"]
+
+[algorithm_sizes]
+"xoroshiro128++" = [64]
+
+[profiles.quick]
+runs = 10
+limits = { max_runs = 100, budget = -1.5 }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(doc.get("runs"), Some(&JsonValue::UInt(1000)));
+        assert_eq!(doc.get("precision").and_then(|v| v.as_f64()), Some(0.5));
+        assert_eq!(doc.get("filter_outliers").and_then(|v| v.as_bool()), Some(true));
+        assert_eq!(doc.get("sizes").and_then(|v| v.as_array()).map(|a| a.len()), Some(2));
+        assert_eq!(doc.get("pin").and_then(|v| v.as_str()), Some("per-call"));
+        assert_eq!(
+            doc.get("variants").and_then(|v| v.as_array()).unwrap()[0].as_str(),
+            Some("dot_product/x86_64-*")
+        );
+        assert!(doc.get("algorithm_sizes").and_then(|t| t.get("xoroshiro128++")).is_some());
+
+        let quick = doc.get("profiles").and_then(|p| p.get("quick")).unwrap();
+        assert_eq!(quick.get("runs"), Some(&JsonValue::UInt(10)));
+        let limits = quick.get("limits").unwrap();
+        assert_eq!(limits.get("max_runs"), Some(&JsonValue::UInt(100)));
+        assert_eq!(limits.get("budget"), Some(&JsonValue::Number(-1.5)));
+    }
+
+    #[test]
+    fn test_dotted_keys() {
+        let doc = parse("a.b = \"x\\ty\"\n[c]\nd.e = 1\n").unwrap();
+        assert_eq!(doc.get("a").and_then(|a| a.get("b")).and_then(|v| v.as_str()), Some("x\ty"));
+        assert_eq!(doc.get("c").and_then(|c| c.get("d")).and_then(|d| d.get("e")), Some(&JsonValue::UInt(1)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("runs = ").unwrap_err().contains("line 1"));
+        assert!(parse("a = 1\na = 2").unwrap_err().contains("Duplicate key 'a' (line 2)"));
+        assert!(parse("[[runs]]").is_err());
+        assert!(parse("a = [1, 2").is_err());
+        assert!(parse("a = \"open").is_err());
+        assert!(parse("a = 1 b = 2").is_err());
+        assert!(parse("a = 1\na.b = 2").is_err());
+    }
+}
@@ -2,7 +2,17 @@
 //!
 //! Handles formatted output for the CLI.
 
-use crate::registry::{AlgorithmRegistry, AlgorithmRunner, BenchmarkResult};
+use crate::registry::{AlgorithmRunner, BenchmarkResult, SizeSpace, VariantCheck, SIZE_INDEPENDENT};
+use crate::utils::bench::{counter_frequency, format_in_unit, format_nanos, measures_cycles, nanos_per_unit, TimerOverhead};
+use crate::utils::clock;
+use crate::utils::cpu_affinity;
+use crate::utils::cpu_features::{self, KNOWN_FEATURES};
+use crate::utils::perf::{self, HwCounter};
+use crate::utils::preflight::Preflight;
+use crate::utils::sysinfo::{BuildInfo, MachineInfo};
+use crate::utils::tags::Language;
+use crate::utils::throughput::{distinct_units, format_rate, rate_of, Work};
+use crate::utils::report::{coefficient_of_variation, relative_error, unit_scale, Comparison, RunReport, Verdict};
 use terminal_size::{terminal_size, Width};
 
 /// Get the current terminal width, constrained to a reasonable range
@@ -14,12 +24,13 @@ fn get_term_width() -> usize {
     }
 }
 
-/// Get sorting priority for a variant based on its name and compiler.
+/// Get sorting priority for a variant based on its tags and compiler.
 /// Lower values sort first.
-/// Order: original (0), Rust (1), C by compiler then name (2), ASM (3)
+/// Order: original (0), Rust (1), C by compiler then name (2), ASM/SIMD (3)
 fn variant_sort_key(result: &BenchmarkResult) -> (u8, String, String) {
     let name = result.name.to_lowercase();
-    let compiler = if name.starts_with("c-") || name.starts_with("c_") {
+    let tags = &result.tags;
+    let compiler = if tags.lang == Language::C {
         crate::utils::C_COMPILER_NAME
             .unwrap_or("unknown")
             .to_lowercase()
@@ -29,13 +40,9 @@ fn variant_sort_key(result: &BenchmarkResult) -> (u8, String, String) {
 
     if name == "original" {
         (0, String::new(), String::new())
-    } else if name.contains("asm")
-        || name.contains("simd")
-        || name.contains("avx")
-        || name.contains("neon")
-    {
+    } else if tags.technique == Some("asm") || tags.simd.is_some() {
         (3, name.clone(), compiler)
-    } else if name.starts_with("c-") || name.starts_with("c_") {
+    } else if tags.lang == Language::C {
         (2, compiler.clone(), name.clone())
     } else {
         (1, name.clone(), String::new())
@@ -57,9 +64,19 @@ pub fn print_algo_info_box(algo: &dyn AlgorithmRunner) {
     let cat_line = format!("Category:  {}", algo.category());
     let desc_line = algo.description();
     let var_line = format!("Variants: {}", variants_str);
+    let skipped_line = {
+        let skipped: Vec<String> = algo
+            .skipped_variants()
+            .iter()
+            .map(|v| format!("{} ({})", v.name, v.reason))
+            .collect();
+        (!skipped.is_empty()).then(|| format!("Skipped:  {}", skipped.join(", ")))
+    };
 
     let content_width = [name_line.len(), cat_line.len(), desc_line.len(), var_line.len()]
-        .iter().cloned().max().unwrap_or(60).min(max_content_width);
+        .iter()
+        .chain(skipped_line.as_ref().map(|l| l.len()).iter())
+        .cloned().max().unwrap_or(60).min(max_content_width);
 
     let border = "─".repeat(content_width + 2);
 
@@ -85,6 +102,13 @@ pub fn print_algo_info_box(algo: &dyn AlgorithmRunner) {
         truncate(&var_line, content_width),
         width = content_width
     );
+    if let Some(line) = &skipped_line {
+        println!(
+            "│ {:<width$} │",
+            truncate(line, content_width),
+            width = content_width
+        );
+    }
     println!("└{}┘", border);
     println!();
 }
@@ -100,17 +124,22 @@ fn truncate(s: &str, width: usize) -> String {
     }
 }
 
-/// Print results table for a single size
-/// If show_size is false, the "Size: X" header line is omitted
-pub fn print_results_table(results: &[BenchmarkResult], size: usize, runs: usize, show_size: bool, filtered: bool) {
+/// Print results table for a single size and set of parameter values
+/// If label is None, the "Size: X, name=value" header line is omitted.
+/// `work` adds a throughput column per unit (see `AlgorithmRunner::work`).
+pub fn print_results_table(results: &[BenchmarkResult], label: Option<&str>, work: &[Work], filtered: bool) {
     if results.is_empty() {
         return;
     }
 
+    let units = distinct_units(work.iter().map(|w| w.unit));
+    // Cycle averages also shown in nanoseconds, with the calibrated counter frequency
+    let nanos_per_cycle = nanos_per_unit().filter(|_| measures_cycles());
+    let extra_width = 11 * units.len() + if nanos_per_cycle.is_some() { 11 } else { 0 };
     let term_width = get_term_width();
-    let fixed_width = 72;
+    let fixed_width = 107 + extra_width;
     let variant_col_width = term_width.saturating_sub(fixed_width).max(15);
-    let table_width = variant_col_width + 64 + 6;
+    let table_width = variant_col_width + 96 + 9 + extra_width;
 
     let baseline_time = results
         .first()
@@ -119,75 +148,410 @@ pub fn print_results_table(results: &[BenchmarkResult], size: usize, runs: usize
 
     let baseline_result = results.first().and_then(|r| r.result_sample);
 
+    // Adaptive sampling gives each variant its own number of runs
+    let min_runs = results.iter().map(|r| r.iterations).min().unwrap_or(0);
+    let max_runs = results.iter().map(|r| r.iterations).max().unwrap_or(0);
+    let mut runs = if min_runs == max_runs {
+        format!("{} runs", min_runs)
+    } else {
+        format!("{}-{} runs", min_runs, max_runs)
+    };
+    // Variants of a group share their batch size
+    let batch_size = results[0].batch_size;
+    if batch_size > 1 {
+        runs.push_str(&format!(" × {} iterations, per-iteration cost", batch_size));
+    }
+
+    runs.push_str(&format!(", {} clock", clock::selected().as_str()));
+
     let filter_note = if filtered { " (filtered)" } else { "" };
-    if show_size {
-        println!("  Size: {} ({} runs{})", size, runs, filter_note);
-    } else if filtered {
-        println!("  {} runs{}", runs, filter_note);
+    if let Some(label) = label {
+        println!("  {} ({}{})", label, runs, filter_note);
+    } else {
+        println!("  {}{}", runs, filter_note);
     }
+    let throughput_header: String = units.iter().map(|u| format!(" {:>10}", u.rate_label())).collect();
+    let time_header = if nanos_per_cycle.is_some() { format!(" {:>10}", "Time") } else { String::new() };
     println!("  {}", "─".repeat(table_width));
     println!(
-        "  {:<v_width$} {:>12} {:>12} {:>12} {:>9} {:>9} {:>10}",
+        "  {:<v_width$} {:>12} {:>8}{} {:>12} {:>12} {:>9} {:>15} {:>9} {:>9} {:>10}{}",
         "Variant",
         "Average",
+        "± 95% CI",
+        time_header,
         "Min",
         "Max",
         "Speedup",
+        "Speedup CI",
+        "p-value",
         "CV",
         "Rel. Error",
+        throughput_header,
         v_width = variant_col_width
     );
     println!("  {}", "─".repeat(table_width));
 
-    for result in results {
-        let speedup = baseline_time / result.avg_time.as_nanos() as f64;
+    for (idx, result) in results.iter().enumerate() {
+        let speedup = result
+            .speedup
+            .as_ref()
+            .map(|s| s.speedup)
+            .unwrap_or_else(|| baseline_time / result.avg_time.as_nanos() as f64);
 
-        let avg_ns = result.avg_time.as_nanos() as f64;
-        let std_dev_ns = result.std_dev.as_nanos() as f64;
+        let cv = coefficient_of_variation(result);
 
-        let cv = if avg_ns > 0.0 {
-            std_dev_ns / avg_ns
-        } else {
-            0.0
-        };
+        let relative_error = relative_error(result.result_sample, baseline_result);
+
+        let display_name = display_name(result);
 
-        let relative_error = match (result.result_sample, baseline_result) {
-            (Some(res), Some(base)) => {
-                let diff = (res - base).abs();
-                if base.abs() > 1e-9 { diff / base.abs() } else { diff }
+        let mut time_str = crate::utils::bench::format_measurement_precise(result.avg_nanos_f64);
+        if result.below_noise_floor {
+            time_str.push('†');
+        }
+        let min_str = crate::utils::bench::format_measurement(result.min_time);
+        let max_str = crate::utils::bench::format_measurement(result.max_time);
+
+        let mean_ci_str = match result.mean_ci {
+            Some((low, high)) if result.avg_nanos_f64 > 0.0 => {
+                format!("±{:.1}%", (high - low) / 2.0 / result.avg_nanos_f64 * 100.0)
             }
-            _ => 0.0,
+            _ => "-".to_string(),
         };
 
-        let display_name =
-            if result.name.starts_with("c-") || result.name.starts_with("c_") {
-                match crate::utils::C_COMPILER_NAME {
-                    Some(c) => format!("{} ({})", result.name, c),
-                    None => result.name.clone(),
-                }
-            } else {
-                result.name.clone()
-            };
+        // The baseline is compared against itself: nothing to report
+        let estimate = result.speedup.as_ref().filter(|_| idx > 0);
+        let speedup_ci_str = match estimate.and_then(|s| s.ci) {
+            Some((low, high)) => format!("[{:.2}, {:.2}]", low, high),
+            None => "-".to_string(),
+        };
+        let p_value_str = match estimate.and_then(|s| s.p_value) {
+            Some(p) => format_p_value(p),
+            None => "-".to_string(),
+        };
 
-        let time_str = crate::utils::bench::format_measurement_precise(result.avg_nanos_f64);
-        let min_str = crate::utils::bench::format_measurement(result.min_time);
-        let max_str = crate::utils::bench::format_measurement(result.max_time);
+        let throughput: String = units
+            .iter()
+            .map(|&unit| {
+                let rate = rate_of(work, unit, result.avg_nanos_f64).map(format_rate);
+                format!(" {:>10}", rate.unwrap_or_else(|| "-".to_string()))
+            })
+            .collect();
+
+        let nanos_str = match nanos_per_cycle {
+            Some(ns) => format!(" {:>10}", format_nanos(result.avg_nanos_f64 * ns)),
+            None => String::new(),
+        };
 
         println!(
-            "  {:<v_width$} {:>12} {:>12} {:>12} {:>8.2}x {:>8.2}% {:>10.2e}",
+            "  {:<v_width$} {:>12} {:>8}{} {:>12} {:>12} {:>8.2}x {:>15} {:>9} {:>8.2}% {:>10.2e}{}",
             truncate(&display_name, variant_col_width),
             time_str,
+            mean_ci_str,
+            nanos_str,
             min_str,
             max_str,
             speedup,
+            speedup_ci_str,
+            p_value_str,
             cv * 100.0,
             relative_error,
+            throughput,
+            v_width = variant_col_width
+        );
+    }
+    if results.iter().any(|r| r.below_noise_floor) {
+        println!("  † within the timer noise floor, or a single unbatched call (cold cache): dominated by the measurement itself");
+    }
+    println!();
+}
+
+/// Print the throughput of every variant across input sizes, one column per
+/// `(size, work, results)`, in the first unit declared by the work (bytes when
+/// declared). A variant's throughput drops at the size where its data stops
+/// fitting in a cache level.
+pub fn print_throughput_by_size(heading: &str, columns: &[(usize, &[Work], &[BenchmarkResult])]) {
+    let Some(unit) = distinct_units(columns.iter().flat_map(|(_, work, _)| work.iter().map(|w| w.unit))).first().copied()
+    else {
+        return;
+    };
+    let mut variants: Vec<&BenchmarkResult> = Vec::new();
+    for result in columns.iter().flat_map(|(_, _, results)| results.iter()) {
+        if !variants.iter().any(|v| v.name == result.name) {
+            variants.push(result);
+        }
+    }
+
+    let term_width = get_term_width();
+    let fixed_width = 11 * columns.len() + 2;
+    let variant_col_width = term_width.saturating_sub(fixed_width).max(15);
+    let table_width = variant_col_width + 11 * columns.len();
+
+    println!("  {} ({} by input size)", heading, unit.rate_label());
+    println!("  {}", "─".repeat(table_width));
+    let sizes: String = columns.iter().map(|(size, _, _)| format!(" {:>10}", size)).collect();
+    println!("  {:<v_width$}{}", "Variant", sizes, v_width = variant_col_width);
+    println!("  {}", "─".repeat(table_width));
+
+    for variant in variants {
+        let rates: String = columns
+            .iter()
+            .map(|(_, work, results)| {
+                let rate = results
+                    .iter()
+                    .find(|r| r.name == variant.name)
+                    .and_then(|r| rate_of(work, unit, r.avg_nanos_f64))
+                    .map(format_rate);
+                format!(" {:>10}", rate.unwrap_or_else(|| "-".to_string()))
+            })
+            .collect();
+        println!(
+            "  {:<v_width$}{}",
+            truncate(&display_name(variant), variant_col_width),
+            rates,
+            v_width = variant_col_width
+        );
+    }
+    println!();
+}
+
+/// Format a p-value with 3 decimals ("<0.001" below that)
+fn format_p_value(p: f64) -> String {
+    if p < 0.001 {
+        "<0.001".to_string()
+    } else {
+        format!("{:.3}", p)
+    }
+}
+
+/// Variant name with the C compiler appended for C variants
+fn display_name(result: &BenchmarkResult) -> String {
+    if result.tags.lang == Language::C {
+        match crate::utils::C_COMPILER_NAME {
+            Some(c) => format!("{} ({})", result.name, c),
+            None => result.name.clone(),
+        }
+    } else {
+        result.name.clone()
+    }
+}
+
+/// Print hardware counter averages per call (only if counters were collected)
+pub fn print_counters_table(results: &[BenchmarkResult]) {
+    if results.iter().all(|r| r.counters.is_none()) {
+        return;
+    }
+
+    let term_width = get_term_width();
+    let fixed_width = 72;
+    let variant_col_width = term_width.saturating_sub(fixed_width).max(15);
+    let table_width = variant_col_width + 64 + 6;
+
+    let fmt = |v: Option<f64>| v.map(|v| format!("{:.1}", v)).unwrap_or_else(|| "-".to_string());
+
+    println!("  Hardware counters (average per call)");
+    println!("  {}", "─".repeat(table_width));
+    println!(
+        "  {:<v_width$} {:>12} {:>12} {:>6} {:>10} {:>10} {:>10}",
+        "Variant",
+        "Core cyc",
+        "Instr",
+        "IPC",
+        "Br-miss",
+        "L1D-miss",
+        "LLC-miss",
+        v_width = variant_col_width
+    );
+    println!("  {}", "─".repeat(table_width));
+
+    for result in results {
+        let Some(counters) = &result.counters else {
+            continue;
+        };
+        println!(
+            "  {:<v_width$} {:>12} {:>12} {:>6} {:>10} {:>10} {:>10}",
+            truncate(&display_name(result), variant_col_width),
+            fmt(counters.get(HwCounter::CoreCycles)),
+            fmt(counters.get(HwCounter::Instructions)),
+            counters.ipc().map(|v| format!("{:.2}", v)).unwrap_or_else(|| "-".to_string()),
+            fmt(counters.get(HwCounter::BranchMisses)),
+            fmt(counters.get(HwCounter::L1dMisses)),
+            fmt(counters.get(HwCounter::LlcMisses)),
             v_width = variant_col_width
         );
     }
     println!();
 }
 
+/// Print the average of every variant on each core, when samples ran on several cores
+pub fn print_core_table(results: &[BenchmarkResult]) {
+    let mut cores: Vec<usize> = results.iter().flat_map(|r| r.per_core.iter().map(|c| c.core)).collect();
+    cores.sort_unstable();
+    cores.dedup();
+    if cores.len() < 2 {
+        return;
+    }
+
+    let term_width = get_term_width();
+    let variant_col_width = term_width.saturating_sub(13 * cores.len() + 4).max(15);
+    let table_width = variant_col_width + 13 * cores.len();
+    let header: String = cores.iter().map(|c| format!(" {:>12}", format!("cpu{}", c))).collect();
+
+    println!("  Average per core ({})", crate::utils::bench::unit_name());
+    println!("  {}", "─".repeat(table_width));
+    println!("  {:<v_width$}{}", "Variant", header, v_width = variant_col_width);
+    println!("  {}", "─".repeat(table_width));
+    for result in results {
+        let means: String = cores
+            .iter()
+            .map(|core| {
+                let mean = result.per_core.iter().find(|c| c.core == *core);
+                format!(" {:>12}", mean.map(|c| format!("{:.2}", c.mean)).unwrap_or_else(|| "-".to_string()))
+            })
+            .collect();
+        println!("  {:<v_width$}{}", truncate(&display_name(result), variant_col_width), means, v_width = variant_col_width);
+    }
+    println!();
+}
+
+/// Print the average of each variant on each core class side by side
+/// (`--core-classes`), with the ratio of each class to the first one
+pub fn print_class_table(classes: &[&str], results: &[&[BenchmarkResult]], label: Option<&str>) {
+    const COL: usize = 22;
+    let term_width = get_term_width();
+    let variant_col_width = term_width.saturating_sub(COL * classes.len() + 4).max(15);
+    let table_width = variant_col_width + COL * classes.len();
+    let header: String = classes.iter().map(|c| format!(" {:>w$}", c, w = COL - 1)).collect();
+
+    match label {
+        Some(label) => println!("  {} - average per core class ({})", label, crate::utils::bench::unit_name()),
+        None => println!("  Average per core class ({})", crate::utils::bench::unit_name()),
+    }
+    println!("  {}", "─".repeat(table_width));
+    println!("  {:<v_width$}{}", "Variant", header, v_width = variant_col_width);
+    println!("  {}", "─".repeat(table_width));
+    for result in results[0] {
+        let name = display_name(result);
+        let first = result.avg_nanos_f64;
+        let cells: String = results
+            .iter()
+            .enumerate()
+            .map(|(i, class)| {
+                let cell = match class.iter().find(|r| display_name(r) == name) {
+                    Some(r) if i > 0 && first > 0.0 => format!("{:.2} ({:.2}x)", r.avg_nanos_f64, r.avg_nanos_f64 / first),
+                    Some(r) => format!("{:.2}", r.avg_nanos_f64),
+                    None => "-".to_string(),
+                };
+                format!(" {:>w$}", cell, w = COL - 1)
+            })
+            .collect();
+        println!("  {:<v_width$}{}", truncate(&name, variant_col_width), cells, v_width = variant_col_width);
+    }
+    println!();
+}
+
+/// Print the per-variant delta table against a baseline snapshot
+pub fn print_comparison_table(
+    baseline_path: &str,
+    baseline: &RunReport,
+    current: &RunReport,
+    comparisons: &[Comparison],
+) {
+    if comparisons.is_empty() {
+        return;
+    }
+
+    let term_width = get_term_width();
+    let fixed_width = 76;
+    let variant_col_width = term_width.saturating_sub(fixed_width).max(15);
+    let table_width = variant_col_width + 68 + 6;
+
+    println!("  Comparison with baseline: {} (seed {})", baseline_path, baseline.seed);
+    if baseline.machine.cpu_model != current.machine.cpu_model {
+        println!(
+            "  Warning: baseline CPU '{}' differs from current CPU '{}'",
+            baseline.machine.cpu_model, current.machine.cpu_model
+        );
+    }
+    if baseline.unit != current.unit {
+        match unit_scale(baseline, current) {
+            Some(_) => println!(
+                "  Note: baseline measured in {}, converted to {} with the calibrated counter frequency",
+                baseline.unit, current.unit
+            ),
+            None => println!(
+                "  Warning: baseline unit '{}' differs from current unit '{}' (no counter frequency to convert, not compared)",
+                baseline.unit, current.unit
+            ),
+        }
+    }
+    if baseline.timing.subtract_overhead != current.timing.subtract_overhead {
+        println!("  Warning: timer overhead subtraction differs between baseline and current run");
+    }
+    println!("  {}", "─".repeat(table_width));
+    println!(
+        "  {:<v_width$} {:>8} {:>16} {:>16} {:>9} {:>9} {:>8}",
+        "Algorithm / Variant",
+        "Size",
+        "Baseline",
+        "Current",
+        "Delta",
+        "p-value",
+        "Verdict",
+        v_width = variant_col_width
+    );
+    println!("  {}", "─".repeat(table_width));
+
+    let mut last_group = None;
+    for cmp in comparisons {
+        if last_group != Some((&cmp.algorithm, &cmp.params)) {
+            if cmp.params.is_empty() {
+                println!("  {}", cmp.algorithm);
+            } else {
+                println!("  {} ({})", cmp.algorithm, cmp.params);
+            }
+            last_group = Some((&cmp.algorithm, &cmp.params));
+        }
+        let baseline_str = cmp
+            .baseline_avg
+            .map(|avg| format_in_unit(avg, &current.unit))
+            .unwrap_or_else(|| "-".to_string());
+        let delta_str = cmp
+            .delta_pct
+            .map(|d| format!("{:+.2}%", d))
+            .unwrap_or_else(|| "-".to_string());
+        let p_str = cmp
+            .p_value
+            .map(|p| format!("{:.2e}", p))
+            .unwrap_or_else(|| "-".to_string());
+
+        println!(
+            "  {:<v_width$} {:>8} {:>16} {:>16} {:>9} {:>9} {:>8}",
+            truncate(&format!("  {}", cmp.variant), variant_col_width),
+            if cmp.input_size == SIZE_INDEPENDENT { "-".to_string() } else { cmp.input_size.to_string() },
+            baseline_str,
+            format_in_unit(cmp.current_avg, &current.unit),
+            delta_str,
+            p_str,
+            cmp.verdict.label(),
+            v_width = variant_col_width
+        );
+    }
+    println!();
+
+    let faster = comparisons.iter().filter(|c| c.verdict == Verdict::Faster).count();
+    let slower = comparisons.iter().filter(|c| c.verdict == Verdict::Slower).count();
+    let incomparable = comparisons.iter().filter(|c| c.verdict == Verdict::Incomparable).count();
+    println!(
+        "  {} faster, {} slower, {} unchanged{} (Welch's t-test, alpha = {})",
+        faster,
+        slower,
+        comparisons.len() - faster - slower - incomparable,
+        if incomparable > 0 { format!(", {} not comparable", incomparable) } else { String::new() },
+        crate::utils::report::DEFAULT_ALPHA
+    );
+    println!();
+}
+
 /// Print the application header
 pub fn print_header() {
     let term_width = get_term_width().min(80); // Cap header at 80
@@ -210,41 +574,228 @@ pub fn print_header() {
 
 /// Print the help message
 pub fn print_help() {
-    println!("Usage: micro-algo [OPTIONS] [ALGORITHM]");
+    println!("Usage: micro-algo [COMMAND] [OPTIONS] [ALGORITHM...]");
+    println!();
+    println!("Commands:");
+    println!("  run              Run benchmarks (default when no command is given)");
+    println!("  verify           Check every variant against its reference; exit code 1 on failure");
+    println!("  list             List algorithms with their variants, tags and required CPU features");
+    println!("  info             Show machine, build, CPU feature and timer diagnostics");
+    println!("  compare A B      Compare result file B against A; exit code 1 on regressions");
     println!();
     println!("Options:");
-    println!("  --list, -l       List all available algorithms");
+    println!("  --list, -l       Same as the 'list' command");
     println!("  --help, -h       Show this help message");
-    println!("  --sizes SIZES    Comma-separated input sizes (default: 64,256,1024,4096,16384)");
-    println!("  --iter, -r N     Number of runs per variant (default: 30)");
-    println!("  --seed N         Random seed for reproducible benchmarks (default: time-based)");
+    println!("  --config FILE    Load settings from a TOML (or .json) config file; options given here override it");
+    println!("  --profile NAME   Apply a named profile ([profiles.NAME]) of the config file");
+    println!("  --category, -c CATS  Run all algorithms of these comma-separated categories");
+    println!("  --sizes SIZES    Comma-separated input sizes (default: per algorithm, else 64,256,1024,4096,16384)");
+    println!("  --sizes ALGO=SIZES  Input sizes for one algorithm only (repeatable)");
+    println!("  --param, -p NAME=VALUES  Only benchmark these values of a parameter (default: all, see 'list')");
+    println!("  --iter, -r N     Number of runs per variant (default: 30; minimum in adaptive mode)");
+    println!("  --precision PCT  Adaptive: sample until the 95% CI of the mean is within ±PCT%");
+    println!("  --time-budget S  Adaptive: wall-clock budget per variant in seconds (default: 2)");
+    println!("  --max-runs N     Adaptive: maximum runs per variant (default: 100000)");
+    println!("  --seed N         Random seed for inputs and task order (default: time-based)");
+    println!("  --variants PATS  Only run variants matching these comma-separated globs (algo/variant or variant)");
+    println!("  --exclude PATS   Skip variants matching these comma-separated globs");
+    println!("  --lang LANG      Only run variants in this language: 'rust' or 'c'");
+    println!("  --arch ARCH      Only run variants for this architecture (e.g. x86_64, or 'none' for portable)");
+    println!("  --simd SET       Only run variants using this SIMD set (e.g. avx2, or 'none' for scalar)");
+    println!("  --technique T    Only run variants using this technique (e.g. asm, or 'none')");
     println!("  --csv FILE       Export timing data to CSV file");
+    println!("  --raw-csv FILE   Export every individual measurement (schedule order) to CSV");
+    println!("  --json FILE      Export full statistics with machine and build metadata to JSON");
     println!("  --filter, -f     Filter outliers (trim 1% extremes from measurements)");
     println!("  --pin MODE       CPU pin strategy: 'global' or 'per-call' (default: per-call)");
+    println!("  --batch MODE     Iterations per sample for batched variants: 'auto', 'off' or N (default: auto)");
+    println!("  --cache MODE     Cache state of each sample: 'hot', 'cold-data', 'cold-code' or 'cold' (default: hot)");
+    println!("  --core N         Pin to core N (default: the first isolated core, else the current core)");
+    println!("  --cores LIST     Rotate the runs of each variant through these cores (e.g. 2,3 or 4-7)");
+    println!("  --core-classes   Run once per core type of a hybrid CPU (P-cores, E-cores) and compare them");
+    println!("  --clock NAME     Clock read around each sample: 'rdtsc', 'rdtscp', 'monotonic' or 'perf' (default: rdtsc)");
+    println!("  --subtract-overhead  Subtract the calibrated empty-measurement cost from samples");
+    println!("  --perf           Collect hardware performance counters (Linux perf_event_open)");
+    println!("  --strict         Refuse to run when the preflight check warns (governor, turbo, SMT, load, TSC)");
+    println!("  --save-baseline FILE  Save all results and machine metadata to a JSON snapshot");
+    println!("  --compare FILE   Compare results against a saved snapshot");
     println!();
     println!("Arguments:");
-    println!("  ALGORITHM        Name of specific algorithm to run (omit for all)");
+    println!("  ALGORITHM...     Names of the algorithms to run (omit for all)");
     println!();
     println!("Examples:");
     println!("  micro-algo                      # Run all algorithms");
     println!("  micro-algo dot_product          # Run only dot_product");
-    println!("  micro-algo --list               # List algorithms");
+    println!("  micro-algo dot_product xoroshiro128++  # Run two algorithms");
+    println!("  micro-algo --category control_flow     # Run a whole category");
+    println!("  micro-algo list                 # List algorithms and variants");
+    println!("  micro-algo verify dot_product   # Check dot_product variants (CI-friendly exit code)");
+    println!("  micro-algo info                 # Machine and timer diagnostics");
+    println!("  micro-algo compare a.json b.json       # Compare two result files");
     println!("  micro-algo --sizes 128,512      # Custom sizes");
+    println!("  micro-algo --sizes dot_product=1024,65536  # Sizes for one algorithm");
+    println!("  micro-algo elseif_vs_jumptable -p pattern=constant  # One parameter value");
     println!("  micro-algo --seed 12345         # Reproducible run");
+    println!("  micro-algo --config bench.toml --profile quick  # Checked-in profile");
+    println!("  micro-algo --technique asm      # Hand-written assembly variants of all algorithms");
+    println!("  micro-algo --variants 'dot_product/x86_64-*,*/original'  # A few variants only");
     println!("  micro-algo --csv data.csv       # Export timings to CSV");
+    println!("  micro-algo --json run.json      # Export results with metadata to JSON");
     println!("  micro-algo --pin global         # Use global CPU pinning");
+    println!("  micro-algo --cores 2,3          # Compare two cores, results per core");
+    println!("  micro-algo --core-classes       # Compare P-cores and E-cores side by side");
+    println!("  micro-algo --clock monotonic    # Measure nanoseconds instead of cycles");
+    println!("  micro-algo --precision 0.5      # Sample until ±0.5% (or 2s per variant)");
+    println!("  micro-algo --perf elseif_vs_jumptable  # Compare branch-misses");
+    println!("  micro-algo --save-baseline base.json   # Save a baseline");
+    println!("  micro-algo --compare base.json         # Compare against it");
 }
 
-/// Print the list of available algorithms
-pub fn print_available_algorithms(registry: &AlgorithmRegistry) {
+/// Print algorithms with their variants (description, tags, required CPU features)
+pub fn print_available_algorithms(algorithms: &[&dyn AlgorithmRunner]) {
     println!("Available algorithms:");
-    println!();
-    for algo in registry.all() {
+    for algo in algorithms {
+        println!();
         println!(
             "  {:<20} [{}] - {}",
             algo.name(),
             algo.category(),
             algo.description()
         );
+        match algo.size_space() {
+            SizeSpace::Any => {}
+            SizeSpace::Defaults(sizes) => println!(
+                "    sizes: {} (default)",
+                sizes.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", ")
+            ),
+            SizeSpace::Independent => println!("    sizes: independent (runs once, --sizes ignored)"),
+        }
+        for parameter in algo.parameters() {
+            println!(
+                "    param {}: {} - {}",
+                parameter.name,
+                parameter.values.join(", "),
+                parameter.description
+            );
+        }
+        for variant in algo.variants() {
+            let features = if variant.required_features.is_empty() {
+                String::new()
+            } else {
+                format!(" (needs {})", variant.required_features.join(", "))
+            };
+            println!(
+                "    {:<22} {:<20} {}{}",
+                variant.name,
+                variant.tags.to_string(),
+                variant.description,
+                features
+            );
+        }
+        for skipped in algo.skipped_variants() {
+            println!("    {:<22} skipped: {}", skipped.name, skipped.reason);
+        }
+    }
+}
+
+/// Print the per-variant verification report of one algorithm.
+///
+/// # Returns
+/// The number of failed variants.
+pub fn print_verification_report(algo: &dyn AlgorithmRunner, checks: &[VariantCheck]) -> usize {
+    println!("  {} [{}]", algo.name(), algo.category());
+    let mut failures = 0;
+    for check in checks {
+        match &check.result {
+            Ok(()) => println!("    PASS  {}", check.name),
+            Err(reason) => {
+                failures += 1;
+                println!("    FAIL  {}: {}", check.name, reason);
+            }
+        }
+    }
+    for skipped in algo.skipped_variants() {
+        println!("    SKIP  {}: {}", skipped.name, skipped.reason);
+    }
+    failures
+}
+
+/// Print the preflight checks of the machine state
+pub fn print_preflight(preflight: &Preflight) {
+    if preflight.cores.is_empty() {
+        println!("  Preflight:");
+    } else {
+        let cores: Vec<String> = preflight.cores.iter().map(|c| format!("cpu{}", c)).collect();
+        println!("  Preflight ({}):", cores.join(", "));
+    }
+    if preflight.checks.is_empty() {
+        println!("    N/A   system checks are only available on Linux");
+    }
+    for check in &preflight.checks {
+        println!("    {:<5} {:<10} {}", check.status.label(), check.name, check.detail);
+    }
+    println!();
+}
+
+/// Print machine, build and measurement diagnostics
+pub fn print_system_info(machine: &MachineInfo, build: &BuildInfo, overhead: &TimerOverhead) {
+    let unit = crate::utils::bench::unit_name();
+    println!("Machine:");
+    println!("  CPU:             {}", machine.cpu_model);
+    println!("  OS / arch:       {} / {}", machine.os, machine.arch);
+    println!("  Kernel:          {}", machine.kernel.as_deref().unwrap_or("unknown"));
+    println!("  Logical cores:   {}", machine.logical_cores);
+    let classes = cpu_affinity::core_classes(&[]);
+    let class_names: Vec<String> = classes
+        .iter()
+        .map(|c| format!("{} cpu{} ({})", c.name, cpu_affinity::format_cpu_list(&c.cores), c.detail))
+        .collect();
+    match classes.len() {
+        0 => println!("  Core types:      unknown"),
+        1 => println!("  Core types:      uniform ({})", classes[0].detail),
+        _ => println!("  Core types:      {}", class_names.join(", ")),
+    }
+    println!("  C compiler:      {}", machine.c_compiler.as_deref().unwrap_or("none"));
+    println!();
+    println!("Build:");
+    println!("  rustc:           {}", build.rustc_version);
+    println!("  Profile:         {}", build.profile);
+    println!("  Cargo features:  {}", join_or_none(&build.cargo_features));
+    println!("  Target features: {}", join_or_none(&build.target_features));
+    println!();
+    println!("CPU features (runtime):");
+    let (supported, missing): (Vec<&str>, Vec<&str>) =
+        KNOWN_FEATURES.iter().partition(|f| cpu_features::is_supported(f));
+    println!("  Supported:       {}", join_or_none(&supported));
+    println!("  Not supported:   {}", join_or_none(&missing));
+    println!();
+    println!("Measurement:");
+    println!("  Clock:           {}", clock::selected().as_str());
+    println!("  Unit:            {}", unit);
+    if clock::selected().reads_tsc() {
+        match counter_frequency() {
+            Some(hz) => println!("  Counter freq:    {:.3} GHz (calibrated against CLOCK_MONOTONIC_RAW)", hz / 1e9),
+            None => println!("  Counter freq:    unknown"),
+        }
+    }
+    println!(
+        "  Timer overhead:  {} {} (noise floor ±{} {}, {} samples)",
+        overhead.median, unit, overhead.noise_floor, unit, overhead.samples
+    );
+    match perf::enable() {
+        Ok(counters) => {
+            perf::disable();
+            let names: Vec<&str> = counters.iter().map(|c| c.name()).collect();
+            println!("  HW counters:     {}", join_or_none(&names));
+        }
+        Err(e) => println!("  HW counters:     unavailable ({})", e),
+    }
+}
+
+fn join_or_none<S: AsRef<str>>(items: &[S]) -> String {
+    if items.is_empty() {
+        "none".to_string()
+    } else {
+        items.iter().map(AsRef::as_ref).collect::<Vec<_>>().join(", ")
     }
 }